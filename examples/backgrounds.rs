@@ -74,6 +74,8 @@ impl ApplicationHandler for App {
                 },
             ],
             spacing: None,
+            padding: None,
+            align: rugui::layout::Align::Stretch,
         });
 
         let row2 = Element::new().with_label("row2");
@@ -111,6 +113,8 @@ impl ApplicationHandler for App {
                 },
             ],
             spacing: None,
+            padding: None,
+            align: rugui::layout::Align::Stretch,
         });
 
         rows.children = rugui::Children::Rows {
@@ -125,6 +129,8 @@ impl ApplicationHandler for App {
                 },
             ],
             spacing: None,
+            padding: None,
+            align: rugui::layout::Align::Stretch,
         };
 
         let entry = gui.add_element(rows);
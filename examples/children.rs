@@ -79,6 +79,8 @@ impl ApplicationHandler for App {
                 },
             ],
             spacing: Size::None,
+            padding: None,
+            align: rugui::layout::Align::Stretch,
         };
 
         let mut columns = Element::new().with_label("columns");
@@ -109,6 +111,8 @@ impl ApplicationHandler for App {
                 },
             ],
             spacing: Size::None,
+            padding: None,
+            align: rugui::layout::Align::Stretch,
         };
 
         bg.children =
@@ -93,13 +93,13 @@ impl ApplicationHandler for App {
                 }
                 col_children.push(Section { element: gui.add_element(columns), size: Size::None })
             }
-            columns.children = Children::Columns { children: col_children, spacing: Size::None };
+            columns.children = Children::Columns { children: col_children, spacing: Size::None, padding: None, align: rugui::layout::Align::Stretch };
             rows_children.push(Section {
                 element: gui.add_element(columns),
                 size: Size::None
             })
         }
-        rows.children = Children::Rows { children: rows_children, spacing: Size::None };
+        rows.children = Children::Rows { children: rows_children, spacing: Size::None, padding: None, align: rugui::layout::Align::Stretch };
 
         let mut drag_element = Element::new().with_label("Drag Element");
         drag_element.styles.set_visible(false);
@@ -201,7 +201,7 @@ impl ApplicationHandler for App {
                     this.drag = None;
                 }
                 Messages::MouseMove => {
-                    let pos = if let WindowEvent::MouseMove { position, .. } = message.window_event {
+                    let pos = if let Some(WindowEvent::MouseMove { position, .. }) = message.window_event {
                         position
                     } else {
                         continue;
@@ -145,6 +145,8 @@ impl ApplicationHandler for App {
                 },
             ],
             spacing: Size::Fill,
+            padding: None,
+            align: rugui::layout::Align::Stretch,
         };
 
         let row3_styles = &mut row3.styles;
@@ -189,6 +191,8 @@ impl ApplicationHandler for App {
                 },
             ],
             spacing: Size::Fill,
+            padding: None,
+            align: rugui::layout::Align::Stretch,
         };
         let entry = gui.add_element(rows);
         gui.set_entry(Some(entry));
@@ -90,7 +90,7 @@ impl ApplicationHandler for App {
                     size: None,
                 }
             ]);
-            row5.children = Children::Columns { children, spacing: None }
+            row5.children = Children::Columns { children, spacing: None, padding: None, align: rugui::layout::Align::Stretch }
         }
 
         let children = Vec::from([
@@ -118,6 +118,8 @@ impl ApplicationHandler for App {
         let key = gui.add_element(rows.with_children(rugui::Children::Rows {
             children,
             spacing: None,
+            padding: None,
+            align: rugui::layout::Align::Stretch,
         }));
         gui.set_entry(Some(key));
 
@@ -2,7 +2,7 @@ use std::sync::Arc;
 
 use examples_common::Drawing;
 use rugui::{
-    styles::{Colors, RValue, Rotation, Side, Value, Values}, Children, Element, Gui
+    styles::{Colors, Edges, RValue, Rotation, Side, Value, Values}, Children, Element, Gui
 };
 use winit::application::ApplicationHandler;
 
@@ -48,6 +48,7 @@ impl ApplicationHandler for App {
 
         let size = window.inner_size();
         let mut gui = Gui::new(size.into(), &drawing.device, &drawing.queue);
+        gui.set_scale_factor(window.scale_factor() as f32);
 
         let mut element = Element::new().with_label("hello element");
         let styles = &mut element.styles;
@@ -55,7 +56,7 @@ impl ApplicationHandler for App {
         styles.max_height.set(Some(Values::Value(Value::Container(RValue::Percent(80.0), Side::Height))));
         styles.rotation.set(Rotation::Deg(25.0));
         styles.bg_color.set(Colors::MAGENTA);
-        styles.text_size.set(Values::Value(Value::Pixel(50.0)));
+        styles.text_size.set(Values::Value(Value::Logical(50.0)));
         element.text_str("A rotated text looks pretty ugly, so maybe try to use it as little as possible.");
 
         let mut small_box = Element::new();
@@ -66,7 +67,7 @@ impl ApplicationHandler for App {
         small_box.styles.transfomr_mut().scale_round = Round::Round;
         *small_box.styles.bg_color_mut() = Color::YELLOW;*/
         small_box.styles.rotation.set(Rotation::AbsNone);
-        small_box.styles.margin.set(Values::Value(Value::Container(RValue::Percent(40.0), Side::Max)));
+        small_box.styles.margin.set(Edges::all(Values::Value(Value::Container(RValue::Percent(20.0), Side::Max))));
         small_box.styles.bg_color.set(Colors::YELLOW);
 
         element.children = Children::Element(gui.add_element(small_box));
@@ -99,6 +100,9 @@ impl ApplicationHandler for App {
                 }
                 examples_common::resize_event(&mut this.gui, &mut this.drawing, size.into());
             }
+            winit::event::WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
+                this.gui.set_scale_factor(scale_factor as f32);
+            }
             winit::event::WindowEvent::CloseRequested => {
                 event_loop.exit();
             }
@@ -0,0 +1,104 @@
+//! Generational storage for `Element`s
+//!
+//! Every slot remembers how many times it has been reused, so an `ElementKey`
+//! that outlived its element can be told apart from one that never existed.
+
+use crate::ElementKey;
+
+struct Slot<T> {
+    generation: u32,
+    value: Option<T>,
+}
+
+pub(crate) struct Arena<T> {
+    slots: Vec<Slot<T>>,
+    free: Vec<u32>,
+}
+
+impl<T> Arena<T> {
+    pub fn new() -> Self {
+        Self {
+            slots: Vec::new(),
+            free: Vec::new(),
+        }
+    }
+
+    pub fn insert(&mut self, value: T) -> ElementKey {
+        match self.free.pop() {
+            Some(index) => {
+                let slot = &mut self.slots[index as usize];
+                slot.value = Some(value);
+                ElementKey {
+                    index,
+                    generation: slot.generation,
+                }
+            }
+            None => {
+                let index = self.slots.len() as u32;
+                self.slots.push(Slot {
+                    generation: 0,
+                    value: Some(value),
+                });
+                ElementKey {
+                    index,
+                    generation: 0,
+                }
+            }
+        }
+    }
+
+    pub fn remove(&mut self, key: ElementKey) -> Option<T> {
+        let slot = self.slots.get_mut(key.index as usize)?;
+        if slot.generation != key.generation {
+            return None;
+        }
+        let value = slot.value.take()?;
+        // a slot whose generation would wrap around is retired for good,
+        // otherwise a very old key could come back to life
+        if let Some(generation) = slot.generation.checked_add(1) {
+            slot.generation = generation;
+            self.free.push(key.index);
+        }
+        Some(value)
+    }
+
+    pub fn get(&self, key: ElementKey) -> Option<&T> {
+        match self.slots.get(key.index as usize) {
+            Some(slot) if slot.generation == key.generation => slot.value.as_ref(),
+            _ => None,
+        }
+    }
+
+    pub fn get_mut(&mut self, key: ElementKey) -> Option<&mut T> {
+        match self.slots.get_mut(key.index as usize) {
+            Some(slot) if slot.generation == key.generation => slot.value.as_mut(),
+            _ => None,
+        }
+    }
+
+    pub fn contains(&self, key: ElementKey) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Returns true if the key was handed out by this arena but its value has since been removed
+    pub fn is_stale(&self, key: ElementKey) -> bool {
+        match self.slots.get(key.index as usize) {
+            Some(slot) => slot.generation != key.generation || slot.value.is_none(),
+            None => false,
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (ElementKey, &T)> {
+        self.slots.iter().enumerate().filter_map(|(index, slot)| {
+            slot.value.as_ref().map(|value| {
+                (
+                    ElementKey {
+                        index: index as u32,
+                        generation: slot.generation,
+                    },
+                    value,
+                )
+            })
+        })
+    }
+}
@@ -0,0 +1,740 @@
+//! Constraint layout
+//!
+//! `Children::Constraints` places children by solving linear equations and
+//! inequalities between their edges, centers and sizes, like Cassowary or
+//! Auto Layout. Every constraint has a strength, weaker constraints give way
+//! when they can't all hold. Without constraints a child keeps the size from
+//! its styles and is centered, so only the things that matter need to be said.
+//!
+//! Positions are relative to the top left corner of the element with the
+//! layout. The solved rectangles are given to children the same way other
+//! layouts give them space, their `position`, `margin` and transforms apply
+//! inside of them.
+
+use std::{
+    ops::{Add, Mul, Sub},
+    sync::Arc,
+};
+
+use crate::{
+    rotate_point,
+    styles::{Container, Values},
+    ElementKey, ElementTransform, Gui, Point,
+};
+
+/// Children placed by constraints
+///
+/// ```ignore
+/// let quarter = Values::Value(Value::ViewPort(RValue::Fraction(0.25), Side::Width));
+/// let panel = Children::Constraints(
+///     ConstraintLayout::new(vec![sidebar, label, slider])
+///         .with_constraint(Constraint::equal(
+///             Attribute::new(label, Anchor::Left),
+///             Attribute::new(slider, Anchor::Left),
+///         ))
+///         .with_constraint(Constraint::greater_or_equal(
+///             Attribute::new(sidebar, Anchor::Width),
+///             Values::Value(Value::Pixel(200.0)),
+///         ))
+///         .with_constraint(
+///             Constraint::equal(Attribute::new(sidebar, Anchor::Width), quarter)
+///                 .with_strength(Strength::Strong),
+///         )
+///         .with_constraint(Constraint::equal(
+///             Attribute::new(sidebar, Anchor::Right) + Values::Value(Value::Pixel(16.0)),
+///             Attribute::new(slider, Anchor::Left),
+///         )),
+/// );
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ConstraintLayout {
+    pub children: Vec<ElementKey>,
+    pub constraints: Vec<Constraint>,
+    /// Required constraints contradicted each other or the solver gave up
+    /// in the last layout
+    conflict: bool,
+    /// Constraints mentioned elements that aren't children in the last layout
+    ignored: bool,
+    /// Rows and solution of the last layout, solved again only when the rows change
+    solved: Option<Arc<Solved>>,
+}
+
+#[derive(Debug)]
+struct Solved {
+    rows: Vec<Row>,
+    solution: Vec<f64>,
+}
+
+impl ConstraintLayout {
+    pub fn new(children: Vec<ElementKey>) -> Self {
+        Self {
+            children,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_constraint(mut self, constraint: Constraint) -> Self {
+        self.constraints.push(constraint);
+        self
+    }
+
+    pub fn with_constraints(mut self, constraints: impl IntoIterator<Item = Constraint>) -> Self {
+        self.constraints.extend(constraints);
+        self
+    }
+
+    /// Returns true if the required constraints couldn't all hold in the last layout
+    ///
+    /// They are then solved as if they were the strongest optional constraints.
+    /// Also true when the solver gave up before finding the best solution, the
+    /// children then keep their last places.
+    pub fn has_conflict(&self) -> bool {
+        self.conflict
+    }
+
+    /// Returns true if constraints mentioned elements that aren't children
+    /// in the last layout, those constraints were left out
+    pub fn has_ignored_constraints(&self) -> bool {
+        self.ignored
+    }
+
+    /// Removes the child and the constraints that mention it
+    pub(crate) fn remove(&mut self, key: ElementKey) {
+        self.children.retain(|child| *child != key);
+        self.constraints
+            .retain(|constraint| !constraint.mentions(key));
+    }
+}
+
+/// Edge, center or size of an element
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Anchor {
+    Left,
+    Right,
+    Top,
+    Bottom,
+    CenterX,
+    CenterY,
+    Width,
+    Height,
+}
+
+/// Anchor of a child or of the element with the layout
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Attribute {
+    /// `None` is the element with the layout
+    pub element: Option<ElementKey>,
+    pub anchor: Anchor,
+}
+
+impl Attribute {
+    pub fn new(element: ElementKey, anchor: Anchor) -> Self {
+        Self {
+            element: Some(element),
+            anchor,
+        }
+    }
+
+    /// Anchor of the element with the layout, it can't be moved by constraints
+    pub fn parent(anchor: Anchor) -> Self {
+        Self {
+            element: None,
+            anchor,
+        }
+    }
+}
+
+/// Sum of attributes and values, each multiplied by a factor
+///
+/// Built with `+`, `-` and `*` from `Attribute`s and `Values`. Values are
+/// resolved against the element with the layout.
+#[derive(Clone, Debug, Default)]
+pub struct LinearExpression {
+    pub terms: Vec<(f32, Attribute)>,
+    pub constants: Vec<(f32, Values)>,
+}
+
+impl LinearExpression {
+    fn scaled(mut self, factor: f32) -> Self {
+        self.terms
+            .iter_mut()
+            .for_each(|(coefficient, _)| *coefficient *= factor);
+        self.constants
+            .iter_mut()
+            .for_each(|(coefficient, _)| *coefficient *= factor);
+        self
+    }
+}
+
+impl From<Attribute> for LinearExpression {
+    fn from(attribute: Attribute) -> Self {
+        Self {
+            terms: vec![(1.0, attribute)],
+            constants: Vec::new(),
+        }
+    }
+}
+
+impl From<Values> for LinearExpression {
+    fn from(values: Values) -> Self {
+        Self {
+            terms: Vec::new(),
+            constants: vec![(1.0, values)],
+        }
+    }
+}
+
+impl<T: Into<LinearExpression>> Add<T> for LinearExpression {
+    type Output = LinearExpression;
+
+    fn add(mut self, rhs: T) -> LinearExpression {
+        let rhs = rhs.into();
+        self.terms.extend(rhs.terms);
+        self.constants.extend(rhs.constants);
+        self
+    }
+}
+
+impl<T: Into<LinearExpression>> Sub<T> for LinearExpression {
+    type Output = LinearExpression;
+
+    fn sub(self, rhs: T) -> LinearExpression {
+        self + rhs.into().scaled(-1.0)
+    }
+}
+
+impl Mul<f32> for LinearExpression {
+    type Output = LinearExpression;
+
+    fn mul(self, rhs: f32) -> LinearExpression {
+        self.scaled(rhs)
+    }
+}
+
+impl<T: Into<LinearExpression>> Add<T> for Attribute {
+    type Output = LinearExpression;
+
+    fn add(self, rhs: T) -> LinearExpression {
+        LinearExpression::from(self) + rhs
+    }
+}
+
+impl<T: Into<LinearExpression>> Sub<T> for Attribute {
+    type Output = LinearExpression;
+
+    fn sub(self, rhs: T) -> LinearExpression {
+        LinearExpression::from(self) - rhs
+    }
+}
+
+impl Mul<f32> for Attribute {
+    type Output = LinearExpression;
+
+    fn mul(self, rhs: f32) -> LinearExpression {
+        LinearExpression::from(self).scaled(rhs)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Relation {
+    Equal,
+    LessOrEqual,
+    GreaterOrEqual,
+}
+
+/// How hard the solver tries to keep a constraint
+///
+/// A broken stronger constraint costs more than breaking weaker ones by
+/// the same distance a thousand times over.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Strength {
+    /// Has to hold
+    #[default]
+    Required,
+    Strong,
+    Medium,
+    /// Same strength as the sizes and centering children start with
+    Weak,
+}
+
+impl Strength {
+    fn weight(self) -> Option<f64> {
+        match self {
+            Strength::Required => None,
+            Strength::Strong => Some(1e6),
+            Strength::Medium => Some(1e3),
+            Strength::Weak => Some(1.0),
+        }
+    }
+}
+
+/// `left` compared to `right`
+#[derive(Clone, Debug)]
+pub struct Constraint {
+    pub left: LinearExpression,
+    pub relation: Relation,
+    pub right: LinearExpression,
+    pub strength: Strength,
+}
+
+impl Constraint {
+    pub fn new(
+        left: impl Into<LinearExpression>,
+        relation: Relation,
+        right: impl Into<LinearExpression>,
+    ) -> Self {
+        Self {
+            left: left.into(),
+            relation,
+            right: right.into(),
+            strength: Strength::Required,
+        }
+    }
+
+    pub fn equal(left: impl Into<LinearExpression>, right: impl Into<LinearExpression>) -> Self {
+        Self::new(left, Relation::Equal, right)
+    }
+
+    pub fn less_or_equal(
+        left: impl Into<LinearExpression>,
+        right: impl Into<LinearExpression>,
+    ) -> Self {
+        Self::new(left, Relation::LessOrEqual, right)
+    }
+
+    pub fn greater_or_equal(
+        left: impl Into<LinearExpression>,
+        right: impl Into<LinearExpression>,
+    ) -> Self {
+        Self::new(left, Relation::GreaterOrEqual, right)
+    }
+
+    pub fn with_strength(mut self, strength: Strength) -> Self {
+        self.strength = strength;
+        self
+    }
+
+    fn mentions(&self, key: ElementKey) -> bool {
+        self.left
+            .terms
+            .iter()
+            .chain(&self.right.terms)
+            .any(|(_, attribute)| attribute.element == Some(key))
+    }
+}
+
+/// Weight of required constraints when they conflict and are solved as optional
+const CONFLICT_WEIGHT: f64 = 1e9;
+
+impl<Msg> Gui<Msg>
+where
+    Msg: Clone,
+{
+    /// Lays out children of a `Children::Constraints` element inside `transform`
+    pub(crate) fn constraints_transform(
+        &mut self,
+        key: ElementKey,
+        mut layout: ConstraintLayout,
+        transform: &ElementTransform,
+    ) {
+        let view_port = self.view_port();
+        let container: Container = transform.clone().into();
+        let size = transform.scale;
+
+        // left, top, width and height of every child
+        let children = layout
+            .children
+            .iter()
+            .copied()
+            .filter(|child| self.elements.get(*child).is_some())
+            .collect::<Vec<_>>();
+        let variable = |key: ElementKey| children.iter().position(|child| *child == key);
+        let mut rows = Vec::new();
+
+        for (index, child) in children.iter().enumerate() {
+            let element = match self.elements.get(*child) {
+                Some(element) => element,
+                None => continue,
+            };
+            let styles = &element.styles;
+            let mut container = Container {
+                elements: self.referenced(*child),
+                ..container.clone()
+            };
+            element.intrinsic.apply(&mut container);
+            let (min_width, max_width) = styles.width_limits(&container, &view_port);
+            let (min_height, max_height) = styles.height_limits(&container, &view_port);
+            let preferred = styles.clamp_size(
+                Point::new(
+                    styles.width.get().calc(&container, &view_port),
+                    styles.height.get().calc(&container, &view_port),
+                ),
+                &container,
+                &view_port,
+            );
+            let single = |column: usize, constant: f32, relation, weight| {
+                let mut coefficients = vec![0.0; children.len() * 4];
+                coefficients[index * 4 + column] = 1.0;
+                Row {
+                    coefficients,
+                    constant: -constant as f64,
+                    relation,
+                    weight,
+                }
+            };
+            rows.push(single(2, 0.0, Relation::GreaterOrEqual, None));
+            rows.push(single(3, 0.0, Relation::GreaterOrEqual, None));
+            for (column, min, max) in [(2, min_width, max_width), (3, min_height, max_height)] {
+                if min > 0.0 {
+                    rows.push(single(column, min, Relation::GreaterOrEqual, Some(1e6)));
+                }
+                if max.is_finite() {
+                    rows.push(single(column, max, Relation::LessOrEqual, Some(1e6)));
+                }
+            }
+            let weak = Strength::Weak.weight();
+            rows.push(single(2, preferred.x.max(0.0), Relation::Equal, weak));
+            rows.push(single(3, preferred.y.max(0.0), Relation::Equal, weak));
+            rows.push(single(
+                0,
+                (size.x - preferred.x) / 2.0,
+                Relation::Equal,
+                weak,
+            ));
+            rows.push(single(
+                1,
+                (size.y - preferred.y) / 2.0,
+                Relation::Equal,
+                weak,
+            ));
+        }
+
+        layout.ignored = false;
+        let first_constraint = rows.len();
+        for constraint in &layout.constraints {
+            // without the missing terms it would be a different constraint
+            if constraint
+                .left
+                .terms
+                .iter()
+                .chain(&constraint.right.terms)
+                .any(|(_, attribute)| attribute.element.is_some_and(|key| variable(key).is_none()))
+            {
+                layout.ignored = true;
+                continue;
+            }
+            let mut row = Row {
+                coefficients: vec![0.0; children.len() * 4],
+                constant: 0.0,
+                relation: constraint.relation,
+                weight: constraint.strength.weight(),
+            };
+            let sides = [(1.0, &constraint.left), (-1.0, &constraint.right)];
+            for (sign, expression) in sides {
+                for (factor, values) in &expression.constants {
+                    row.constant += (sign * factor * values.calc(&container, &view_port)) as f64;
+                }
+                for (factor, attribute) in &expression.terms {
+                    let factor = sign * factor;
+                    let element = match attribute.element.and_then(variable) {
+                        Some(index) => index,
+                        None => {
+                            row.constant += (factor * parent_anchor(attribute.anchor, size)) as f64;
+                            continue;
+                        }
+                    };
+                    // anchors are sums of the left or top edge and the size
+                    let (edge, length) = match attribute.anchor {
+                        Anchor::Left => (Some(0), None),
+                        Anchor::Top => (Some(1), None),
+                        Anchor::Width => (None, Some((2, 1.0))),
+                        Anchor::Height => (None, Some((3, 1.0))),
+                        Anchor::Right => (Some(0), Some((2, 1.0))),
+                        Anchor::Bottom => (Some(1), Some((3, 1.0))),
+                        Anchor::CenterX => (Some(0), Some((2, 0.5))),
+                        Anchor::CenterY => (Some(1), Some((3, 0.5))),
+                    };
+                    if let Some(edge) = edge {
+                        row.coefficients[element * 4 + edge] += factor as f64;
+                    }
+                    if let Some((length, part)) = length {
+                        row.coefficients[element * 4 + length] += (factor * part) as f64;
+                    }
+                }
+            }
+            rows.push(row);
+        }
+        // the rows capture the constraints, children and the container size,
+        // the tableau is only built and solved again when one of them changes
+        let variables = children.len() * 4;
+        let solution = match &layout.solved {
+            Some(solved) if solved.rows == rows => solved.solution.clone(),
+            _ => {
+                let solution = match solve(variables, &rows) {
+                    Ok(solution) => {
+                        layout.conflict = false;
+                        Some(solution)
+                    }
+                    Err(_) => {
+                        layout.conflict = true;
+                        let mut soft = rows.clone();
+                        for row in &mut soft[first_constraint..] {
+                            row.weight.get_or_insert(CONFLICT_WEIGHT);
+                        }
+                        solve(variables, &soft).ok()
+                    }
+                };
+                let solution = solution
+                    .or_else(|| {
+                        layout
+                            .solved
+                            .as_ref()
+                            .map(|solved| solved.solution.clone())
+                            .filter(|solution| solution.len() == variables)
+                    })
+                    .unwrap_or_else(|| vec![0.0; variables]);
+                layout.solved = Some(Arc::new(Solved {
+                    rows,
+                    solution: solution.clone(),
+                }));
+                solution
+            }
+        };
+        if let Some(element) = self.elements.get_mut(key) {
+            element.children = crate::Children::Constraints(layout);
+        }
+
+        let origin = Point::new(
+            transform.position.x - transform.scale.x / 2.0,
+            transform.position.y - transform.scale.y / 2.0,
+        );
+        for (index, child) in children.into_iter().enumerate() {
+            let value =
+                |column: usize| solution.get(index * 4 + column).copied().unwrap_or(0.0) as f32;
+            let size = Point::new(value(2).max(0.0), value(3).max(0.0));
+            let point = Point::new(
+                origin.x + value(0) + size.x / 2.0,
+                origin.y + value(1) + size.y / 2.0,
+            );
+            let position = if transform.rotation == 0.0 {
+                point
+            } else {
+                rotate_point(point, transform.position, transform.rotation)
+            };
+            let slot = ElementTransform {
+                position,
+                scale: size,
+                rotation: transform.rotation,
+                matrix: transform.matrix,
+            };
+            self.sized_element_transform(child, &slot, Some(size));
+        }
+    }
+}
+
+fn parent_anchor(anchor: Anchor, size: Point) -> f32 {
+    match anchor {
+        Anchor::Left | Anchor::Top => 0.0,
+        Anchor::Right | Anchor::Width => size.x,
+        Anchor::Bottom | Anchor::Height => size.y,
+        Anchor::CenterX => size.x / 2.0,
+        Anchor::CenterY => size.y / 2.0,
+    }
+}
+
+/// `coefficients · variables + constant` compared to 0
+#[derive(Clone, Debug, PartialEq)]
+struct Row {
+    coefficients: Vec<f64>,
+    constant: f64,
+    relation: Relation,
+    /// `None` for required rows
+    weight: Option<f64>,
+}
+
+const EPSILON: f64 = 1e-7;
+
+/// Why `solve` returned no values
+#[derive(Debug, PartialEq)]
+enum Unsolved {
+    /// Required rows can't all hold
+    Infeasible,
+    /// The simplex method didn't finish in its iteration limit
+    IterationLimit,
+}
+
+/// Returns values of the variables that satisfy the required rows and
+/// break the optional rows as little as possible by their weight
+///
+/// Variables may be negative, each one is split into a positive and a
+/// negative part for the simplex method.
+fn solve(variables: usize, rows: &[Row]) -> Result<Vec<f64>, Unsolved> {
+    let mut columns = variables * 2;
+    // columns of slack and error variables of every row, with their cost
+    let mut extra = Vec::with_capacity(rows.len());
+    for row in rows {
+        let mut row_columns = Vec::new();
+        match (row.relation, row.weight) {
+            (Relation::Equal, None) => (),
+            (Relation::GreaterOrEqual, None) => row_columns.push((-1.0, 0.0)),
+            (Relation::LessOrEqual, None) => row_columns.push((1.0, 0.0)),
+            (Relation::Equal, Some(weight)) => {
+                row_columns.push((-1.0, weight));
+                row_columns.push((1.0, weight));
+            }
+            (Relation::GreaterOrEqual, Some(weight)) => {
+                row_columns.push((1.0, weight));
+                row_columns.push((-1.0, 0.0));
+            }
+            (Relation::LessOrEqual, Some(weight)) => {
+                row_columns.push((-1.0, weight));
+                row_columns.push((1.0, 0.0));
+            }
+        }
+        extra.push((columns, row_columns.clone()));
+        columns += row_columns.len();
+    }
+    // one artificial variable per row starts as the basis
+    let artificial = columns;
+    let width = columns + rows.len() + 1;
+
+    let mut cost = vec![0.0; columns];
+    let mut tableau = Vec::with_capacity(rows.len());
+    for (index, (row, (start, row_columns))) in rows.iter().zip(&extra).enumerate() {
+        let mut line = vec![0.0; width];
+        for (variable, coefficient) in row.coefficients.iter().enumerate() {
+            line[variable * 2] = *coefficient;
+            line[variable * 2 + 1] = -coefficient;
+        }
+        for (offset, (coefficient, weight)) in row_columns.iter().enumerate() {
+            line[start + offset] = *coefficient;
+            cost[start + offset] = *weight;
+        }
+        line[width - 1] = -row.constant;
+        if line[width - 1] < 0.0 {
+            line.iter_mut().for_each(|value| *value = -*value);
+        }
+        line[artificial + index] = 1.0;
+        tableau.push(line);
+    }
+    let mut basis = (artificial..artificial + rows.len()).collect::<Vec<_>>();
+
+    // find a point where the required rows hold
+    let mut feasibility = vec![0.0; width - 1];
+    feasibility[artificial..]
+        .iter_mut()
+        .for_each(|cost| *cost = 1.0);
+    if !simplex(&mut tableau, &mut basis, &feasibility, width - 1) {
+        return Err(Unsolved::IterationLimit);
+    }
+    let infeasibility = tableau
+        .iter()
+        .zip(&basis)
+        .filter(|(_, column)| **column >= artificial)
+        .map(|(line, _)| line[width - 1])
+        .sum::<f64>();
+    if infeasibility > EPSILON * (1.0 + rows.len() as f64) {
+        return Err(Unsolved::Infeasible);
+    }
+    for index in 0..tableau.len() {
+        if basis[index] < artificial {
+            continue;
+        }
+        if let Some(column) = (0..artificial).find(|column| tableau[index][*column].abs() > EPSILON)
+        {
+            pivot(&mut tableau, &mut basis, index, column);
+        }
+    }
+
+    // then the cheapest one
+    cost.resize(width - 1, 0.0);
+    if !simplex(&mut tableau, &mut basis, &cost, artificial) {
+        return Err(Unsolved::IterationLimit);
+    }
+
+    let mut values = vec![0.0; columns];
+    for (line, column) in tableau.iter().zip(&basis) {
+        if *column < columns {
+            values[*column] = line[width - 1];
+        }
+    }
+    Ok((0..variables)
+        .map(|variable| values[variable * 2] - values[variable * 2 + 1])
+        .collect())
+}
+
+/// Minimizes `cost` with Bland's rule, only columns before `allowed` enter the basis
+///
+/// Returns false if it stopped at the iteration limit before the minimum.
+fn simplex(tableau: &mut [Vec<f64>], basis: &mut [usize], cost: &[f64], allowed: usize) -> bool {
+    let rhs = match tableau.first() {
+        Some(line) => line.len() - 1,
+        None => return true,
+    };
+    // reduced costs, kept up to date by every pivot like the other lines
+    let mut objective = cost.to_vec();
+    objective.resize(rhs + 1, 0.0);
+    for (line, basic) in tableau.iter().zip(basis.iter()) {
+        let factor = cost[*basic];
+        if factor != 0.0 {
+            objective
+                .iter_mut()
+                .zip(line)
+                .for_each(|(value, line)| *value -= factor * line);
+        }
+    }
+    let mut basic = vec![false; rhs];
+    basis.iter().for_each(|column| basic[*column] = true);
+
+    let limit = 50 * (tableau.len() + rhs);
+    for _ in 0..limit {
+        let entering =
+            match (0..allowed).find(|column| !basic[*column] && objective[*column] < -EPSILON) {
+                Some(column) => column,
+                None => return true,
+            };
+        let leaving = tableau
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| line[entering] > EPSILON)
+            .map(|(index, line)| (index, line[rhs] / line[entering]))
+            .min_by(|(a, ratio_a), (b, ratio_b)| {
+                ratio_a.total_cmp(ratio_b).then(basis[*a].cmp(&basis[*b]))
+            });
+        let index = match leaving {
+            Some((index, _)) => index,
+            // costs are never negative, this can't happen
+            None => return true,
+        };
+        basic[basis[index]] = false;
+        basic[entering] = true;
+        pivot(tableau, basis, index, entering);
+        let factor = objective[entering];
+        objective
+            .iter_mut()
+            .zip(&tableau[index])
+            .for_each(|(value, line)| *value -= factor * line);
+    }
+    false
+}
+
+fn pivot(tableau: &mut [Vec<f64>], basis: &mut [usize], index: usize, column: usize) {
+    let divisor = tableau[index][column];
+    tableau[index]
+        .iter_mut()
+        .for_each(|value| *value /= divisor);
+    let line = tableau[index].clone();
+    for (other, target) in tableau.iter_mut().enumerate() {
+        if other == index {
+            continue;
+        }
+        let factor = target[column];
+        if factor != 0.0 {
+            target
+                .iter_mut()
+                .zip(&line)
+                .for_each(|(value, pivot)| *value -= factor * pivot);
+        }
+    }
+    basis[index] = column;
+}
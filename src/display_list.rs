@@ -0,0 +1,202 @@
+//! Renderer-agnostic description of a frame
+//!
+//! `Gui::display_list` flattens the laid out `Element` tree into an ordered list
+//! of `DrawCommand`s. Both the wgpu and the software renderer draw from it and
+//! so can any other backend.
+
+use std::sync::Arc;
+
+use crate::{
+    render::{LinearGradientData, RadialGradientData, RenderElementData},
+    rotate_point,
+    styles::{Color, Overflow, Rectangle},
+    texture::Texture,
+    Children, ElementKey, ElementTransform, Gui, Point,
+};
+
+/// Single step of drawing a frame
+#[derive(Debug, Clone)]
+pub enum DrawCommand {
+    /// Quad covering the element
+    Quad {
+        key: ElementKey,
+        data: RenderElementData,
+        fill: Fill,
+    },
+    /// Text of the element, laid out inside its quad
+    Text {
+        key: ElementKey,
+        data: RenderElementData,
+        text: String,
+        color: Color,
+    },
+    /// Everything until the matching `PopClip` is clipped to the element
+    PushClip {
+        key: ElementKey,
+        data: RenderElementData,
+    },
+    /// Ends the last `PushClip`
+    PopClip,
+}
+
+/// Describes how a quad is filled
+#[derive(Debug, Clone)]
+pub enum Fill {
+    Color(Color),
+    LinearGradient(LinearGradientData),
+    RadialGradient(RadialGradientData),
+    Texture(Arc<Texture>),
+}
+
+impl<Msg> Gui<Msg>
+where
+    Msg: Clone,
+{
+    /// Returns everything that should be drawn, back to front
+    ///
+    /// Reflects the layout computed by the last `update`
+    pub fn display_list(&self) -> Vec<DrawCommand> {
+        let mut list = Vec::new();
+        let mut clips: Vec<ElementKey> = Vec::new();
+        for key in &self.ordered {
+            let element = match self.get_element(*key) {
+                Some(element) => element,
+                None => continue,
+            };
+            let chain = self.clip_chain(*key);
+            let common = clips.iter().zip(&chain).take_while(|(a, b)| a == b).count();
+            for _ in common..clips.len() {
+                list.push(DrawCommand::PopClip);
+            }
+            for clip in &chain[common..] {
+                if let Some(element) = self.get_element(*clip) {
+                    list.push(DrawCommand::PushClip {
+                        key: *clip,
+                        data: element.render_element.1,
+                    });
+                }
+            }
+            clips = chain;
+            let data = element.render_element.1;
+            let mut quad = |fill| {
+                list.push(DrawCommand::Quad {
+                    key: *key,
+                    data,
+                    fill,
+                })
+            };
+            if let Some(texture) = element.styles.bg_texture.get() {
+                quad(Fill::Texture(texture.clone()));
+            }
+            if let Some(grad) = data.rad_grad {
+                quad(Fill::RadialGradient(grad));
+            }
+            if let Some(grad) = data.lin_grad {
+                quad(Fill::LinearGradient(grad));
+            }
+            quad(Fill::Color(data.color));
+            if let Some(text) = element.text() {
+                list.push(DrawCommand::Text {
+                    key: *key,
+                    data,
+                    text: text.clone(),
+                    color: element.styles.text_color.get().to_rgba().into(),
+                });
+            }
+        }
+        for _ in 0..clips.len() {
+            list.push(DrawCommand::PopClip);
+        }
+        list
+    }
+
+    /// Returns ancestors that clip the element, outermost first
+    pub(crate) fn clip_chain(&self, key: ElementKey) -> Vec<ElementKey> {
+        let mut chain = self
+            .ancestors(key)
+            .filter(|ancestor| {
+                self.get_element(*ancestor).is_some_and(|element| {
+                    matches!(element.children, Children::Scroll(_))
+                        || *element.styles.overflow.get() == Overflow::Hidden
+                })
+            })
+            .collect::<Vec<_>>();
+        chain.reverse();
+        chain
+    }
+
+    /// Returns true if the point is inside every ancestor that clips the element
+    /// and inside the letterbox
+    pub(crate) fn unclipped(&self, key: ElementKey, point: Point) -> bool {
+        if let Some(letterbox) = self.letterbox() {
+            let (min, max) = (
+                letterbox.position,
+                Point::new(
+                    letterbox.position.x + letterbox.size.x,
+                    letterbox.position.y + letterbox.size.y,
+                ),
+            );
+            if point.x < min.x || point.y < min.y || point.x > max.x || point.y > max.y {
+                return false;
+            }
+        }
+        self.clip_chain(key).into_iter().all(|clip| {
+            self.get_element(clip)
+                .is_some_and(|element| clip_contains(&element.render_element.1, point))
+        })
+    }
+}
+
+/// Returns true if the clip can't be done with a scissor rect
+pub(crate) fn needs_stencil(data: &RenderElementData) -> bool {
+    data.rotation != 0.0 || data.edges[0] > 0.0 || !data.transform.is_axis_aligned()
+}
+
+/// Returns true if the point is inside the rotated and rounded shape of the element,
+/// same as `fs_main` in `clip.wgsl`
+pub(crate) fn clip_contains(data: &RenderElementData, point: Point) -> bool {
+    let point = match data.transform.inverse() {
+        Some(inverse) => inverse.apply(point),
+        None => return false,
+    };
+    let local = rotate_point(
+        Point::new(point.x - data.center[0], point.y - data.center[1]),
+        Point::new(0.0, 0.0),
+        -data.rotation,
+    );
+    let p = [local.x.abs() * 2.0, local.y.abs() * 2.0];
+    if p[0] > data.size[0] || p[1] > data.size[1] {
+        return false;
+    }
+    let edge_size = data.edges[0] * 2.0;
+    let s = [data.size[0] - edge_size, data.size[1] - edge_size];
+    p[0] < s[0] || p[1] < s[1] || Point::from(p).distance(s.into()) < edge_size
+}
+
+/// Returns the axis-aligned box around the element described by `data`
+pub(crate) fn bounds(data: &RenderElementData) -> Rectangle {
+    ElementTransform {
+        position: data.center.into(),
+        scale: data.size.into(),
+        rotation: data.rotation,
+        matrix: data.transform,
+    }
+    .rect()
+    .bounds
+}
+
+/// Returns the part of `a` that is also inside `b`
+pub(crate) fn intersect(a: &Rectangle, b: &Rectangle) -> Rectangle {
+    let min = Point::new(
+        a.position.x.max(b.position.x),
+        a.position.y.max(b.position.y),
+    );
+    let max = Point::new(
+        (a.position.x + a.size.x).min(b.position.x + b.size.x),
+        (a.position.y + a.size.y).min(b.position.y + b.size.y),
+    );
+    Rectangle {
+        position: min,
+        size: Point::new((max.x - min.x).max(0.0), (max.y - min.y).max(0.0)),
+    }
+}
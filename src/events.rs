@@ -69,6 +69,8 @@ pub enum ElementEvent {
     },
     Select,
     Unselect,
+    /// The element was removed from the `Gui`
+    Removed,
 }
 
 impl ElementEvent {
@@ -112,6 +114,7 @@ pub enum EventTypes {
     Scroll,
     Input,
     Select,
+    Removed,
 }
 
 impl From<WindowEvent> for EventTypes {
@@ -149,7 +152,8 @@ where
     Msg: Clone,
 {
     pub event_type: EventTypes,
-    pub window_event: WindowEvent,
+    /// Input that caused the event, `None` for `EventTypes::Removed`
+    pub window_event: Option<WindowEvent>,
     pub element_event: ElementEvent,
     pub msg: Msg,
     pub key: ElementKey,
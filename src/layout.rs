@@ -0,0 +1,766 @@
+//! Layout containers that size their children themselves
+
+use crate::{
+    rotate_point,
+    styles::{Container, Values},
+    ElementKey, ElementTransform, Gui, Point, Section,
+};
+
+/// Positions children along a line, similar to CSS flexbox
+///
+/// Children are placed one after another along the main axis. Leftover space
+/// is shared by children with `grow`, missing space is taken from children
+/// with `shrink`. An empty element with `grow` works as a flexible spacer:
+///
+/// ```ignore
+/// let toolbar = Children::Flex(
+///     Flex::new(FlexDirection::Row, vec![
+///         FlexItem::new(title),
+///         FlexItem::new(spacer).with_grow(1.0),
+///         FlexItem::new(close_button),
+///     ])
+///     .with_gap(Values::Value(Value::Pixel(8.0))),
+/// );
+/// ```
+#[derive(Clone, Debug)]
+pub struct Flex {
+    pub children: Vec<FlexItem>,
+    pub direction: FlexDirection,
+    /// Moves children that don't fit onto a new line
+    pub wrap: bool,
+    /// Distribution of leftover space along the main axis
+    pub justify: Justify,
+    /// Placement of children on the cross axis
+    pub align: Align,
+    /// Space between children and between lines
+    pub gap: Option<Values>,
+}
+
+impl Flex {
+    pub fn new(direction: FlexDirection, children: Vec<FlexItem>) -> Self {
+        Self {
+            children,
+            direction,
+            wrap: false,
+            justify: Justify::Start,
+            align: Align::Stretch,
+            gap: None,
+        }
+    }
+
+    pub fn with_wrap(mut self, wrap: bool) -> Self {
+        self.wrap = wrap;
+        self
+    }
+
+    pub fn with_justify(mut self, justify: Justify) -> Self {
+        self.justify = justify;
+        self
+    }
+
+    pub fn with_align(mut self, align: Align) -> Self {
+        self.align = align;
+        self
+    }
+
+    pub fn with_gap(mut self, gap: Values) -> Self {
+        self.gap = Some(gap);
+        self
+    }
+}
+
+/// Child of a `Flex` container
+#[derive(Clone, Debug)]
+pub struct FlexItem {
+    /// Child `Element`
+    pub element: ElementKey,
+    /// Share of the leftover space the child takes
+    pub grow: f32,
+    /// Share of the missing space the child gives up, weighted by its basis
+    pub shrink: f32,
+    /// Size along the main axis before growing or shrinking
+    ///
+    /// `None` uses `width` or `height` of the child
+    pub basis: Option<Values>,
+}
+
+impl FlexItem {
+    pub fn new(element: ElementKey) -> Self {
+        Self {
+            element,
+            grow: 0.0,
+            shrink: 1.0,
+            basis: None,
+        }
+    }
+
+    pub fn with_grow(mut self, grow: f32) -> Self {
+        self.grow = grow;
+        self
+    }
+
+    pub fn with_shrink(mut self, shrink: f32) -> Self {
+        self.shrink = shrink;
+        self
+    }
+
+    pub fn with_basis(mut self, basis: Values) -> Self {
+        self.basis = Some(basis);
+        self
+    }
+}
+
+/// Main axis of a `Flex` container
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum FlexDirection {
+    /// Left to right
+    #[default]
+    Row,
+    /// Right to left
+    RowReverse,
+    /// Top to bottom
+    Column,
+    /// Bottom to top
+    ColumnReverse,
+}
+
+/// Distribution of leftover space along the main axis
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Justify {
+    /// Children are packed at the start
+    #[default]
+    Start,
+    /// Children are packed at the end
+    End,
+    /// Children are packed in the middle
+    Center,
+    /// Space is put between children, first and last child touch the edges
+    SpaceBetween,
+    /// Every child gets the same space on both of its sides
+    SpaceAround,
+    /// Space between children and the edges is the same
+    SpaceEvenly,
+}
+
+/// Placement of children on the cross axis
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Align {
+    Start,
+    Center,
+    End,
+    /// Children fill the whole cross axis
+    #[default]
+    Stretch,
+}
+
+impl Align {
+    /// Returns offset of a child of `size` inside of `space`
+    pub(crate) fn offset(&self, size: f32, space: f32) -> f32 {
+        match self {
+            Align::Start | Align::Stretch => 0.0,
+            Align::Center => (space - size) / 2.0,
+            Align::End => space - size,
+        }
+    }
+}
+
+/// Child of a `Flex` container while it is being resolved
+struct FlexSlot {
+    element: ElementKey,
+    grow: f32,
+    shrink: f32,
+    basis: f32,
+    main: f32,
+    cross: f32,
+    min_main: f32,
+    max_main: f32,
+    min_cross: f32,
+    max_cross: f32,
+    frozen: bool,
+}
+
+impl FlexSlot {
+    fn clamp_main(&self, size: f32) -> f32 {
+        size.min(self.max_main).max(self.min_main).max(0.0)
+    }
+}
+
+impl<Msg> Gui<Msg>
+where
+    Msg: Clone,
+{
+    /// Lays out children of a `Flex` container inside `transform`
+    pub(crate) fn flex_transform(&mut self, flex: &Flex, transform: &ElementTransform) {
+        let view_port = self.view_port();
+        let container: Container = transform.clone().into();
+        let calc = |value: &Values| value.calc(&container, &view_port);
+        let row = matches!(
+            flex.direction,
+            FlexDirection::Row | FlexDirection::RowReverse
+        );
+        let reverse = matches!(
+            flex.direction,
+            FlexDirection::RowReverse | FlexDirection::ColumnReverse
+        );
+        let (main_size, cross_size) = match row {
+            true => (transform.scale.x, transform.scale.y),
+            false => (transform.scale.y, transform.scale.x),
+        };
+        let gap = flex.gap.as_ref().map(calc).unwrap_or(0.0).max(0.0);
+
+        let mut slots = Vec::with_capacity(flex.children.len());
+        for item in &flex.children {
+            let element = match self.elements.get(item.element) {
+                Some(element) => element,
+                None => continue,
+            };
+            let styles = &element.styles;
+            let mut container = Container {
+                elements: self.referenced(item.element),
+                ..container.clone()
+            };
+            element.intrinsic.apply(&mut container);
+            let calc = |value: &Values| value.calc(&container, &view_port);
+            let (min_width, max_width) = styles.width_limits(&container, &view_port);
+            let (min_height, max_height) = styles.height_limits(&container, &view_port);
+            let width = (calc(styles.width.get()), min_width, max_width);
+            let height = (calc(styles.height.get()), min_height, max_height);
+            let (main, cross) = match row {
+                true => (width, height),
+                false => (height, width),
+            };
+            let mut slot = FlexSlot {
+                element: item.element,
+                grow: item.grow.max(0.0),
+                shrink: item.shrink.max(0.0),
+                basis: item.basis.as_ref().map(calc).unwrap_or(main.0),
+                main: 0.0,
+                cross: cross.0.min(cross.2).max(cross.1).max(0.0),
+                min_main: main.1,
+                max_main: main.2,
+                min_cross: cross.1,
+                max_cross: cross.2,
+                frozen: false,
+            };
+            slot.main = slot.clamp_main(slot.basis);
+            slots.push(slot);
+        }
+
+        let mut lines = Vec::new();
+        let mut start = 0;
+        let mut used = 0.0;
+        for (i, slot) in slots.iter().enumerate() {
+            if flex.wrap && i > start && used + gap + slot.main > main_size {
+                lines.push(start..i);
+                start = i;
+            }
+            used = match i > start {
+                true => used + gap + slot.main,
+                false => slot.main,
+            };
+        }
+        if start < slots.len() {
+            lines.push(start..slots.len());
+        }
+
+        let origin = Point::new(
+            transform.position.x - transform.scale.x / 2.0,
+            transform.position.y - transform.scale.y / 2.0,
+        );
+        let mut cross_start = 0.0;
+        for line in lines {
+            let line = &mut slots[line];
+            let gaps = gap * (line.len() - 1) as f32;
+            resolve_flexible_lengths(line, main_size - gaps);
+            let line_cross = match flex.wrap {
+                true => line.iter().fold(0.0f32, |max, slot| max.max(slot.cross)),
+                false => cross_size,
+            };
+
+            let remaining = main_size - gaps - line.iter().map(|slot| slot.main).sum::<f32>();
+            let count = line.len() as f32;
+            let (mut main_start, between) = match flex.justify {
+                Justify::Start => (0.0, 0.0),
+                Justify::End => (remaining, 0.0),
+                Justify::Center => (remaining / 2.0, 0.0),
+                _ if remaining <= 0.0 => (0.0, 0.0),
+                Justify::SpaceBetween if line.len() > 1 => (0.0, remaining / (count - 1.0)),
+                Justify::SpaceBetween => (0.0, 0.0),
+                Justify::SpaceAround => (remaining / count / 2.0, remaining / count),
+                Justify::SpaceEvenly => (remaining / (count + 1.0), remaining / (count + 1.0)),
+            };
+
+            for slot in line.iter() {
+                let cross = match flex.align {
+                    Align::Stretch => line_cross.min(slot.max_cross).max(slot.min_cross),
+                    _ => slot.cross,
+                };
+                let main = match reverse {
+                    true => main_size - main_start - slot.main,
+                    false => main_start,
+                };
+                let cross_offset = cross_start + flex.align.offset(cross, line_cross);
+                let (center, size) = match row {
+                    true => (
+                        Point::new(main + slot.main / 2.0, cross_offset + cross / 2.0),
+                        Point::new(slot.main, cross),
+                    ),
+                    false => (
+                        Point::new(cross_offset + cross / 2.0, main + slot.main / 2.0),
+                        Point::new(cross, slot.main),
+                    ),
+                };
+                let point = Point::new(origin.x + center.x, origin.y + center.y);
+                let position = if transform.rotation == 0.0 {
+                    point
+                } else {
+                    rotate_point(point, transform.position, transform.rotation)
+                };
+                let child = ElementTransform {
+                    position,
+                    scale: size,
+                    rotation: transform.rotation,
+                    matrix: transform.matrix,
+                };
+                self.sized_element_transform(slot.element, &child, Some(size));
+                main_start += slot.main + gap + between;
+            }
+            cross_start += line_cross + gap;
+        }
+    }
+}
+
+/// Grows or shrinks children of a line so they fill `available` space
+///
+/// Children that hit their min or max size are frozen and the rest
+/// of the space is shared again among the others.
+fn resolve_flexible_lengths(line: &mut [FlexSlot], available: f32) {
+    let grow = line.iter().map(|slot| slot.main).sum::<f32>() < available;
+    for slot in line.iter_mut() {
+        let factor = match grow {
+            true => slot.grow,
+            false => slot.shrink * slot.basis,
+        };
+        slot.frozen = factor <= 0.0;
+    }
+    // every pass freezes at least one child
+    for _ in 0..line.len() {
+        if line.iter().all(|slot| slot.frozen) {
+            break;
+        }
+        let free = available
+            - line
+                .iter()
+                .map(|slot| if slot.frozen { slot.main } else { slot.basis })
+                .sum::<f32>();
+        let factors = line
+            .iter()
+            .filter(|slot| !slot.frozen)
+            .map(|slot| match grow {
+                true => slot.grow,
+                false => slot.shrink * slot.basis,
+            })
+            .sum::<f32>();
+        let mut violation = 0.0;
+        let mut targets = Vec::with_capacity(line.len());
+        for slot in line.iter_mut() {
+            if slot.frozen {
+                targets.push(slot.main);
+                continue;
+            }
+            let factor = match grow {
+                true => slot.grow,
+                false => slot.shrink * slot.basis,
+            };
+            let target = slot.basis + free * factor / factors;
+            slot.main = slot.clamp_main(target);
+            violation += slot.main - target;
+            targets.push(target);
+        }
+        // children clamped in the direction of the total violation keep their size
+        for (slot, target) in line.iter_mut().zip(targets) {
+            if slot.frozen {
+                continue;
+            }
+            slot.frozen = match violation {
+                v if v > 0.0 => slot.main > target,
+                v if v < 0.0 => slot.main < target,
+                _ => true,
+            };
+        }
+    }
+}
+
+/// Positions children in cells of a table
+///
+/// Children are placed by cell and can span several columns or rows.
+/// Rows or columns past the defined tracks are added as `Track::Auto`.
+///
+/// ```ignore
+/// let inventory = Children::Grid(
+///     Grid::new(
+///         vec![Track::Fixed(Values::Value(Value::Pixel(64.0))), Track::Fraction(1.0)],
+///         vec![Track::Auto; 4],
+///         slots,
+///     )
+///     .with_gap(Values::Value(Value::Pixel(4.0))),
+/// );
+/// ```
+#[derive(Clone, Debug)]
+pub struct Grid {
+    pub children: Vec<GridItem>,
+    pub columns: Vec<Track>,
+    pub rows: Vec<Track>,
+    /// Space between columns
+    pub column_gap: Option<Values>,
+    /// Space between rows
+    pub row_gap: Option<Values>,
+}
+
+impl Grid {
+    pub fn new(columns: Vec<Track>, rows: Vec<Track>, children: Vec<GridItem>) -> Self {
+        Self {
+            children,
+            columns,
+            rows,
+            column_gap: None,
+            row_gap: None,
+        }
+    }
+
+    /// Sets both column and row gap
+    pub fn with_gap(mut self, gap: Values) -> Self {
+        self.column_gap = Some(gap.clone());
+        self.row_gap = Some(gap);
+        self
+    }
+
+    pub fn with_column_gap(mut self, gap: Values) -> Self {
+        self.column_gap = Some(gap);
+        self
+    }
+
+    pub fn with_row_gap(mut self, gap: Values) -> Self {
+        self.row_gap = Some(gap);
+        self
+    }
+}
+
+/// Size of a grid column or row
+#[derive(Clone, Debug)]
+pub enum Track {
+    /// Size resolved against the grid
+    Fixed(Values),
+    /// Share of the space left after fixed and auto tracks
+    Fraction(f32),
+    /// Largest preferred size of the children placed only in this track,
+    /// measured from their content and kept within their min and max
+    Auto,
+}
+
+/// Child of a `Grid` container
+#[derive(Clone, Debug)]
+pub struct GridItem {
+    /// Child `Element`
+    pub element: ElementKey,
+    /// First column of the child, starting at 0
+    pub column: usize,
+    /// First row of the child, starting at 0
+    pub row: usize,
+    /// Number of columns the child covers
+    pub column_span: usize,
+    /// Number of rows the child covers
+    pub row_span: usize,
+}
+
+impl GridItem {
+    pub fn new(element: ElementKey, column: usize, row: usize) -> Self {
+        Self {
+            element,
+            column,
+            row,
+            column_span: 1,
+            row_span: 1,
+        }
+    }
+
+    pub fn with_span(mut self, column_span: usize, row_span: usize) -> Self {
+        self.column_span = column_span.max(1);
+        self.row_span = row_span.max(1);
+        self
+    }
+}
+
+impl<Msg> Gui<Msg>
+where
+    Msg: Clone,
+{
+    /// Lays out children of a `Grid` container inside `transform`
+    pub(crate) fn grid_transform(&mut self, grid: &Grid, transform: &ElementTransform) {
+        let view_port = self.view_port();
+        let container: Container = transform.clone().into();
+        let calc = |value: &Values| value.calc(&container, &view_port);
+        let column_gap = grid.column_gap.as_ref().map(calc).unwrap_or(0.0).max(0.0);
+        let row_gap = grid.row_gap.as_ref().map(calc).unwrap_or(0.0).max(0.0);
+
+        let items = grid
+            .children
+            .iter()
+            .filter_map(|item| {
+                let element = self.elements.get(item.element)?;
+                let mut container = Container {
+                    elements: self.referenced(item.element),
+                    ..container.clone()
+                };
+                element.intrinsic.apply(&mut container);
+                // auto tracks fit the content, not the grid the child would fill
+                let size = element.styles.clamp_size(
+                    element.intrinsic.preferred,
+                    &container,
+                    &view_port,
+                );
+                Some((item, size))
+            })
+            .collect::<Vec<_>>();
+        let columns = resolve_tracks(
+            &grid.columns,
+            items
+                .iter()
+                .map(|(item, size)| (item.column, item.column_span, size.x)),
+            Some(transform.scale.x),
+            column_gap,
+            &calc,
+        );
+        let rows = resolve_tracks(
+            &grid.rows,
+            items
+                .iter()
+                .map(|(item, size)| (item.row, item.row_span, size.y)),
+            Some(transform.scale.y),
+            row_gap,
+            &calc,
+        );
+
+        let origin = Point::new(
+            transform.position.x - transform.scale.x / 2.0,
+            transform.position.y - transform.scale.y / 2.0,
+        );
+        for (item, _) in items {
+            let (x, width) = span(&columns, item.column, item.column_span, column_gap);
+            let (y, height) = span(&rows, item.row, item.row_span, row_gap);
+            let point = Point::new(origin.x + x + width / 2.0, origin.y + y + height / 2.0);
+            let position = if transform.rotation == 0.0 {
+                point
+            } else {
+                rotate_point(point, transform.position, transform.rotation)
+            };
+            let cell = ElementTransform {
+                position,
+                scale: Point::new(width, height),
+                rotation: transform.rotation,
+                matrix: transform.matrix,
+            };
+            self.element_transform(item.element, &cell);
+        }
+    }
+}
+
+/// Returns sizes of all tracks
+///
+/// `items` are `(first track, span, size)` of the children, tracks they reach
+/// past the defined ones are treated as `Track::Auto`. Fraction tracks share
+/// the `available` space left by the others, without it they fit their
+/// children like `Track::Auto`, which is how grids are measured.
+pub(crate) fn resolve_tracks(
+    tracks: &[Track],
+    items: impl Iterator<Item = (usize, usize, f32)> + Clone,
+    available: Option<f32>,
+    gap: f32,
+    calc: &impl Fn(&Values) -> f32,
+) -> Vec<f32> {
+    let count = items
+        .clone()
+        .map(|(start, span, _)| start + span.max(1))
+        .max()
+        .unwrap_or(0)
+        .max(tracks.len());
+    let track = |i: usize| tracks.get(i).unwrap_or(&Track::Auto);
+    let mut sizes = (0..count)
+        .map(|i| match track(i) {
+            Track::Fixed(value) => calc(value).max(0.0),
+            Track::Fraction(_) | Track::Auto => 0.0,
+        })
+        .collect::<Vec<_>>();
+    // spanning children are left out, they would need their size split between tracks
+    for (start, span, size) in items {
+        let fits = match track(start) {
+            Track::Auto => true,
+            Track::Fraction(_) => available.is_none(),
+            Track::Fixed(_) => false,
+        };
+        if span <= 1 && fits {
+            sizes[start] = sizes[start].max(size.max(0.0));
+        }
+    }
+    let available = match available {
+        Some(available) => available,
+        None => return sizes,
+    };
+    let fractions = (0..count)
+        .map(|i| match track(i) {
+            Track::Fraction(fr) => fr.max(0.0),
+            _ => 0.0,
+        })
+        .sum::<f32>();
+    if fractions > 0.0 {
+        let gaps = gap * count.saturating_sub(1) as f32;
+        let free = (available - gaps - sizes.iter().sum::<f32>()).max(0.0);
+        for (i, size) in sizes.iter_mut().enumerate() {
+            if let Track::Fraction(fr) = track(i) {
+                *size = free * fr.max(0.0) / fractions.max(1.0);
+            }
+        }
+    }
+    sizes
+}
+
+/// Returns offset and size of tracks `start..start + span`
+fn span(tracks: &[f32], start: usize, span: usize, gap: f32) -> (f32, f32) {
+    let end = (start + span.max(1)).min(tracks.len());
+    let offset = tracks[..start].iter().map(|size| size + gap).sum::<f32>();
+    let size = tracks[start..end].iter().sum::<f32>() + gap * (end - start - 1) as f32;
+    (offset, size)
+}
+
+impl<Msg> Gui<Msg>
+where
+    Msg: Clone,
+{
+    /// Lays out children of `Children::Rows` or `Children::Columns` inside `transform`
+    pub(crate) fn sections_transform(
+        &mut self,
+        sections: &[Section],
+        spacing: Option<&Values>,
+        padding: Option<&Values>,
+        align: Align,
+        transform: &ElementTransform,
+        rows: bool,
+    ) {
+        let view_port = self.view_port();
+        let container: Container = transform.clone().into();
+        let calc = |value: &Values| value.calc(&container, &view_port);
+        let (main_size, cross_size) = match rows {
+            true => (transform.scale.y, transform.scale.x),
+            false => (transform.scale.x, transform.scale.y),
+        };
+        let spacing = spacing.map(calc).unwrap_or(0.0).max(0.0);
+        let padding = padding.map(calc).unwrap_or(0.0).max(0.0);
+
+        let limits = sections
+            .iter()
+            .map(|section| match self.elements.get(section.element) {
+                Some(element) => {
+                    let mut container = Container {
+                        elements: self.referenced(section.element),
+                        ..container.clone()
+                    };
+                    element.intrinsic.apply(&mut container);
+                    match rows {
+                        true => element.styles.height_limits(&container, &view_port),
+                        false => element.styles.width_limits(&container, &view_port),
+                    }
+                }
+                None => (0.0, f32::INFINITY),
+            })
+            .collect::<Vec<_>>();
+        let clamp = |size: f32, (min, max): (f32, f32)| size.min(max).max(min);
+        let mut sizes = sections
+            .iter()
+            .zip(&limits)
+            .map(|(section, limits)| section.size.as_ref().map(|size| clamp(calc(size), *limits)))
+            .collect::<Vec<_>>();
+        // sections without a size share what is left, the ones that hit
+        // their limits keep them and the rest is shared again
+        let gaps = spacing * sections.len().saturating_sub(1) as f32;
+        let available = main_size - padding * 2.0 - gaps;
+        loop {
+            let shared = sizes.iter().filter(|size| size.is_none()).count();
+            if shared == 0 {
+                break;
+            }
+            let free = available - sizes.iter().flatten().sum::<f32>();
+            let share = free.max(0.0) / shared as f32;
+            let mut clamped = false;
+            for (size, limits) in sizes.iter_mut().zip(&limits) {
+                if size.is_none() && clamp(share, *limits) != share {
+                    *size = Some(clamp(share, *limits));
+                    clamped = true;
+                }
+            }
+            if !clamped {
+                for size in sizes.iter_mut().filter(|size| size.is_none()) {
+                    *size = Some(share);
+                }
+            }
+        }
+
+        let origin = Point::new(
+            transform.position.x - transform.scale.x / 2.0,
+            transform.position.y - transform.scale.y / 2.0,
+        );
+        let mut main_start = padding;
+        for (section, size) in sections.iter().zip(sizes) {
+            let space = size.unwrap_or_default().max(0.0);
+            let (cross, size) = match align {
+                Align::Stretch => (cross_size, None),
+                _ => {
+                    let element = match self.elements.get(section.element) {
+                        Some(element) => element,
+                        None => continue,
+                    };
+                    let styles = &element.styles;
+                    let mut slot = Container {
+                        size: match rows {
+                            true => Point::new(cross_size, space),
+                            false => Point::new(space, cross_size),
+                        },
+                        elements: self.referenced(section.element),
+                        ..container.clone()
+                    };
+                    element.intrinsic.apply(&mut slot);
+                    let size = Point::new(
+                        styles.width.get().calc(&slot, &view_port),
+                        styles.height.get().calc(&slot, &view_port),
+                    );
+                    let size = styles.clamp_size(size, &slot, &view_port);
+                    (if rows { size.x } else { size.y }, Some(size))
+                }
+            };
+            let cross_start = align.offset(cross, cross_size);
+            let (center, scale) = match rows {
+                true => (
+                    Point::new(cross_start + cross / 2.0, main_start + space / 2.0),
+                    Point::new(cross, space),
+                ),
+                false => (
+                    Point::new(main_start + space / 2.0, cross_start + cross / 2.0),
+                    Point::new(space, cross),
+                ),
+            };
+            let point = Point::new(origin.x + center.x, origin.y + center.y);
+            let position = if transform.rotation == 0.0 {
+                point
+            } else {
+                rotate_point(point, transform.position, transform.rotation)
+            };
+            let cell = ElementTransform {
+                position,
+                scale,
+                rotation: transform.rotation,
+                matrix: transform.matrix,
+            };
+            self.sized_element_transform(section.element, &cell, size);
+            main_start += space + spacing;
+        }
+    }
+}
@@ -5,18 +5,33 @@
 
 use std::collections::HashMap;
 
+use arena::Arena;
+use display_list::DrawCommand;
 #[cfg(feature = "clipboard")]
 use clipboard::{ClipboardContext, ClipboardProvider};
 use events::{ElementEvent, EventPoll, EventTypes, WindowEvent};
 use cosmic_text::{Attrs, FontSystem, Metrics, SwashCache};
 use image::{DynamicImage, GenericImage};
-use render::{GpuBound, LinearGradientData, RadialGradientData, RenderElement, RenderElementData, RenderLinearGradient, RenderRadialGradient};
+use render::{GpuBound, RenderElement, RenderLinearGradient, RenderRadialGradient};
+pub use render::{LinearGradientData, RadialGradientData, RenderElementData};
 use styles::{Values, ViewPort};
 
+mod arena;
+pub mod constraints;
+pub mod display_list;
 pub mod events;
+pub mod layout;
+pub mod list;
+mod measure;
+mod references;
 mod render;
+pub mod scroll;
+pub mod selector;
+pub mod software;
 pub mod styles;
 pub mod texture;
+pub mod transform;
+pub mod tree;
 #[cfg(feature = "winit")]
 pub mod winit;
 
@@ -27,17 +42,30 @@ pub struct Gui<Msg>
 where
     Msg: Clone,
 {
-    elements: HashMap<ElementKey, Element<Msg>>,
+    elements: Arena<Element<Msg>>,
     events: EventPoll<Msg>,
     entry: Option<ElementKey>,
-    last_key: u64,
     size: (u32, u32),
-    gpu: GpuBound,
+    /// Physical pixels per logical pixel
+    scale_factor: f32,
+    scale_mode: styles::ScaleMode,
+    gpu: Option<GpuBound>,
+    /// Clips are drawn into a stencil attachment
+    stencil: bool,
     input: InputState,
     font_system: Option<FontSystem>,
     swash_cache: Option<SwashCache>,
     select: Select,
     ordered: Vec<ElementKey>,
+    labels: HashMap<String, Vec<ElementKey>>,
+    /// Row builders of `Children::List` elements
+    row_builders: HashMap<ElementKey, list::RowBuilder<Msg>>,
+    /// Rows of a list were created or removed during layout
+    rows_changed: bool,
+    /// Elements that reference each other and can't be laid out in order
+    layout_cycles: Vec<ElementKey>,
+    /// Removed elements still named as the entry or as children
+    stale_keys: Vec<ElementKey>,
     #[cfg(feature = "clipboard")]
     clipboard_ctx: Option<ClipboardContext>,
 }
@@ -47,6 +75,7 @@ struct InputState {
     pub(crate) prev_mouse: Point,
     pub(crate) hover: Option<ElementKey>,
     pub(crate) control_pressed: bool,
+    pub(crate) drag: Option<scroll::ScrollDrag>,
 }
 
 pub(crate) struct Select {
@@ -70,14 +99,19 @@ impl InputState {
             prev_mouse: Point::new(0.0, 0.0),
             hover: None,
             control_pressed: false,
+            drag: None,
         }
     }
 }
 
 /// Key helps you access elements managed by the `Gui`
+///
+/// Keys are generational, once an element is removed its key goes stale
+/// and will never point to another element, even if the slot is reused.
 #[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
 pub struct ElementKey {
-    id: u64,
+    index: u32,
+    generation: u32,
 }
 
 impl<Msg> Gui<Msg>
@@ -85,26 +119,154 @@ where
     Msg: Clone,
 {
     pub fn new(size: (u32, u32), device: &wgpu::Device, queue: &wgpu::Queue) -> Self {
-        let gpu = GpuBound::new(queue, device, size);
-        let this = Self {
-            elements: HashMap::new(),
+        let mut this = Self::new_headless(size);
+        this.gpu = Some(GpuBound::new(queue, device, size, false));
+        this
+    }
+
+    /// Format of the stencil attachment expected by a `Gui` created with `new_with_stencil`
+    pub const STENCIL_FORMAT: wgpu::TextureFormat = GpuBound::STENCIL_FORMAT;
+
+    /// Creates a `Gui` that clips rotated and rounded elements exactly
+    ///
+    /// The render pass given to `render` must have a `STENCIL_FORMAT`
+    /// depth stencil attachment with the stencil cleared to 0
+    pub fn new_with_stencil(size: (u32, u32), device: &wgpu::Device, queue: &wgpu::Queue) -> Self {
+        let mut this = Self::new_headless(size);
+        this.stencil = true;
+        this.gpu = Some(GpuBound::new(queue, device, size, true));
+        this
+    }
+
+    /// Creates a `Gui` without touching the GPU
+    ///
+    /// Layout and events work as usual, GPU resources are created
+    /// on the first call to `prepare`
+    pub fn new_headless(size: (u32, u32)) -> Self {
+        Self {
+            elements: Arena::new(),
             events: EventPoll {
                 events: Vec::new(),
                 queue: Vec::new(),
             },
-            last_key: 0,
             entry: None,
             size,
-            gpu,
+            scale_factor: 1.0,
+            scale_mode: styles::ScaleMode::ConstantPixelSize,
+            gpu: None,
+            stencil: false,
             input: InputState::new(),
             font_system: Some(FontSystem::new()),
             swash_cache: Some(SwashCache::new()),
             select: Select::new(),
             ordered: Vec::new(),
+            labels: HashMap::new(),
+            row_builders: HashMap::new(),
+            rows_changed: false,
+            layout_cycles: Vec::new(),
+            stale_keys: Vec::new(),
             #[cfg(feature = "clipboard")]
             clipboard_ctx: ClipboardContext::new().ok(),
+        }
+    }
+
+    /// Returns the number of physical pixels per logical pixel
+    pub fn scale_factor(&self) -> f32 {
+        self.scale_factor
+    }
+
+    /// Sets the number of physical pixels per logical pixel
+    ///
+    /// `Value::Logical` sizes, including the default text size, are multiplied
+    /// by it while the `ScaleMode` is `ConstantPixelSize`, other modes compute
+    /// their own factor from the window size and ignore it. Layout, rendering and input stay in physical pixels. With the
+    /// `winit` feature `winit::event` keeps it up to date, set the initial
+    /// value from `Window::scale_factor`.
+    pub fn set_scale_factor(&mut self, scale_factor: f32) {
+        self.scale_factor = scale_factor;
+    }
+
+    pub fn scale_mode(&self) -> styles::ScaleMode {
+        self.scale_mode
+    }
+
+    /// Sets how the layout follows the size of the window
+    pub fn set_scale_mode(&mut self, scale_mode: styles::ScaleMode) {
+        self.scale_mode = scale_mode;
+    }
+
+    /// Returns the number of physical pixels per logical pixel after the `ScaleMode`
+    pub fn ui_scale(&self) -> f32 {
+        self.view_port().2
+    }
+
+    /// Returns the part of the window the layout fills, in physical pixels
+    pub fn canvas(&self) -> styles::Rectangle {
+        self.root_transform().rect().bounds
+    }
+
+    /// Converts a point in physical pixels, like a mouse position, to logical
+    /// pixels from the top left corner of the canvas
+    pub fn to_logical(&self, point: Point) -> Point {
+        let (canvas, scale) = (self.canvas(), self.ui_scale());
+        Point::new(
+            (point.x - canvas.position.x) / scale,
+            (point.y - canvas.position.y) / scale,
+        )
+    }
+
+    /// Converts a point in logical pixels from the top left corner of the canvas
+    /// to physical pixels
+    pub fn to_physical(&self, point: Point) -> Point {
+        let (canvas, scale) = (self.canvas(), self.ui_scale());
+        Point::new(
+            point.x * scale + canvas.position.x,
+            point.y * scale + canvas.position.y,
+        )
+    }
+
+    /// Returns what `Values` are resolved against besides the container
+    pub(crate) fn view_port(&self) -> ViewPort {
+        let window = Point::new(self.size.0 as f32, self.size.1 as f32);
+        let (reference, fit) = match self.scale_mode {
+            styles::ScaleMode::ConstantPixelSize => {
+                return ViewPort(window.x, window.y, self.scale_factor)
+            }
+            styles::ScaleMode::ScaleWithScreen { reference, fit } => (reference, fit),
+            styles::ScaleMode::Letterbox { reference } => (reference, styles::ScreenMatch::Expand),
         };
-        this
+        let (width, height) = (window.x / reference.0, window.y / reference.1);
+        let scale = match fit {
+            styles::ScreenMatch::Width => width,
+            styles::ScreenMatch::Height => height,
+            styles::ScreenMatch::Expand => width.min(height),
+        };
+        let scale = if scale.is_finite() { scale } else { self.scale_factor };
+        match self.scale_mode {
+            styles::ScaleMode::Letterbox { .. } => {
+                ViewPort(reference.0 * scale, reference.1 * scale, scale)
+            }
+            _ => ViewPort(window.x, window.y, scale),
+        }
+    }
+
+    /// Returns the canvas if nothing should be drawn or hovered outside of it
+    pub(crate) fn letterbox(&self) -> Option<styles::Rectangle> {
+        match self.scale_mode {
+            styles::ScaleMode::Letterbox { .. } => Some(self.canvas()),
+            _ => None,
+        }
+    }
+
+    /// Returns the space given to the entry element
+    pub(crate) fn root_transform(&self) -> ElementTransform {
+        let view_port = self.view_port();
+        ElementTransform {
+            position: Point::new(self.size.0 as f32 / 2.0, self.size.1 as f32 / 2.0),
+            scale: Point::new(view_port.0, view_port.1),
+            rotation: 0.0,
+            matrix: transform::Matrix::IDENTITY,
+        }
     }
 
     pub fn poll_event(&mut self) -> Option<events::Event<Msg>> {
@@ -112,35 +274,226 @@ where
     }
 
     pub fn add_element(&mut self, element: Element<Msg>) -> ElementKey {
-        let key = ElementKey { id: self.last_key };
-        self.last_key += 1;
-        self.elements.insert(key, element);
+        let children = element.children.keys();
+        let label = element.label.clone();
+        let key = self.elements.insert(element);
+        self.adopt(key, &children);
+        if let Some(label) = label {
+            self.labels.entry(label).or_default().push(key);
+        }
         key
     }
 
+    /// Changes label of an element and keeps the label index in sync
+    pub fn set_label(&mut self, key: ElementKey, label: Option<&str>) {
+        let element = match self.elements.get_mut(key) {
+            Some(element) => element,
+            None => return,
+        };
+        let old = std::mem::replace(&mut element.label, label.map(str::to_string));
+        if let Some(old) = old {
+            self.unindex_label(key, &old);
+        }
+        if let Some(label) = label {
+            self.labels.entry(label.to_string()).or_default().push(key);
+        }
+    }
+
+    /// Returns the first element with the given label
+    pub fn find_by_label(&self, label: &str) -> Option<ElementKey> {
+        self.find_all_by_label(label).into_iter().next()
+    }
+
+    /// Returns all elements with the given label in the order they were added
+    pub fn find_all_by_label(&self, label: &str) -> Vec<ElementKey> {
+        match self.labels.get(label) {
+            Some(keys) => keys
+                .iter()
+                .copied()
+                .filter(|key| {
+                    self.get_element(*key)
+                        .is_some_and(|element| element.label.as_deref() == Some(label))
+                })
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    fn unindex_label(&mut self, key: ElementKey, label: &str) {
+        if let Some(keys) = self.labels.get_mut(label) {
+            keys.retain(|k| *k != key);
+            if keys.is_empty() {
+                self.labels.remove(label);
+            }
+        }
+    }
+
+    /// Replaces children of an element and keeps parent links in sync
+    ///
+    /// Children that belonged to another element are taken out of its
+    /// children. Prefer this over assigning to `Element::children` of an added
+    /// element directly, that leaves the parent links of the children stale.
+    pub fn set_children(&mut self, key: ElementKey, children: Children) {
+        let new = children.keys();
+        let old = match self.elements.get_mut(key) {
+            Some(element) => std::mem::replace(&mut element.children, children).keys(),
+            None => return,
+        };
+        for child in old {
+            if let Some(element) = self.elements.get_mut(child) {
+                if element.parent == Some(key) {
+                    element.parent = None;
+                }
+            }
+        }
+        self.adopt(key, &new);
+    }
+
+    /// Links the children to the parent and takes them from their old parent
+    fn adopt(&mut self, parent: ElementKey, children: &[ElementKey]) {
+        for child in children {
+            let old = match self.elements.get_mut(*child) {
+                Some(element) => element.parent.replace(parent),
+                None => continue,
+            };
+            if old.is_some_and(|old| old != parent) {
+                self.detach(*child, old);
+            }
+        }
+    }
+
+    /// Removes a single element
+    ///
+    /// Children of the element are left in the `Gui`, use `remove_subtree`
+    /// to get rid of them as well
     pub fn remove_element(&mut self, key: ElementKey) {
-        self.elements.remove(&key);
+        if let Some(element) = self.elements.remove(key) {
+            self.detach(key, element.parent);
+            for child in element.children.keys() {
+                if let Some(child) = self.elements.get_mut(child) {
+                    child.parent = None;
+                }
+            }
+            self.on_removed(key, &element);
+            self.drop_dangling_keys();
+        }
+    }
+
+    /// Removes the element together with all of its descendants
+    ///
+    /// GPU resources of every removed element are freed and elements
+    /// listening to `EventTypes::Removed` are notified
+    pub fn remove_subtree(&mut self, key: ElementKey) {
+        if let Some(parent) = self.parent_of(key) {
+            self.detach(key, Some(parent));
+        }
+        self.remove_subtree_except(key, None);
+    }
+
+    /// Removes the key from children of its parent
+    fn detach(&mut self, key: ElementKey, parent: Option<ElementKey>) {
+        if let Some(parent) = parent.and_then(|parent| self.elements.get_mut(parent)) {
+            parent.children.remove(key);
+        }
+    }
+
+    fn remove_subtree_except(&mut self, key: ElementKey, keep: Option<ElementKey>) {
+        if let Some(element) = keep.and_then(|keep| self.elements.get_mut(keep)) {
+            element.parent = None;
+        }
+        let mut stack = vec![key];
+        while let Some(key) = stack.pop() {
+            if Some(key) == keep {
+                continue;
+            }
+            let element = match self.elements.remove(key) {
+                Some(element) => element,
+                None => continue,
+            };
+            stack.extend(element.children.keys());
+            self.on_removed(key, &element);
+        }
+        self.drop_dangling_keys();
+    }
+
+    fn on_removed(&mut self, key: ElementKey, element: &Element<Msg>) {
+        if let Some(label) = &element.label {
+            self.unindex_label(key, label);
+        }
+        if let Some(listeners) = element.events.get(&EventTypes::Removed) {
+            for EventListener { msg, .. } in listeners {
+                self.events.events.push(events::Event {
+                    event_type: EventTypes::Removed,
+                    window_event: None,
+                    element_event: ElementEvent::Removed,
+                    msg,
+                    key,
+                });
+            }
+        }
+    }
+
+    /// Forgets every key that no longer points to an element
+    fn drop_dangling_keys(&mut self) {
+        let elements = &self.elements;
+        self.ordered.retain(|key| elements.contains(*key));
+        self.select.selectables.retain(|key| elements.contains(*key));
+        if self.input.hover.is_some_and(|key| !elements.contains(key)) {
+            self.input.hover = None;
+        }
+        if self.select.selected.is_some_and(|key| !elements.contains(key)) {
+            self.select.selected = None;
+        }
+        if self.entry.is_some_and(|key| !elements.contains(key)) {
+            self.entry = None;
+        }
+        self.row_builders.retain(|key, _| elements.contains(*key));
     }
 
     pub fn get_element(&self, key: ElementKey) -> Option<&Element<Msg>> {
-        self.elements.get(&key).map(|element| element)
+        self.elements.get(key)
     }
 
     pub fn get_element_mut(&mut self, key: ElementKey) -> Option<&mut Element<Msg>> {
-        self.elements.get_mut(&key).map(|element| element)
+        self.elements.get_mut(key)
+    }
+
+    /// Returns true if the key points to a live element
+    pub fn contains(&self, key: ElementKey) -> bool {
+        self.elements.contains(key)
     }
 
+    /// Returns true if the element behind the key has been removed
+    ///
+    /// Unlike `contains` this tells apart a removed element from a key
+    /// that was never handed out by this `Gui`
+    pub fn is_stale(&self, key: ElementKey) -> bool {
+        self.elements.is_stale(key)
+    }
+
+    /// Returns removed elements that the last `update` found as the entry or
+    /// in children of another element
+    ///
+    /// They are skipped by the layout, remove them from the children or set
+    /// a new entry.
+    pub fn stale_keys(&self) -> &[ElementKey] {
+        &self.stale_keys
+    }
+
+    /// Sets the root element
+    ///
+    /// The previous root is removed together with its descendants, except for
+    /// the new root if it was part of the old tree
     pub fn set_entry(&mut self, key: Option<ElementKey>) {
         if let Some(entry) = self.entry.take() {
-            self.remove_element(entry);
+            if Some(entry) != key {
+                self.remove_subtree_except(entry, key);
+            }
         }
         self.entry = key;
         if let Some(key) = key {
-            let transform = ElementTransform {
-                position: Point::new(self.size.0 as f32 / 2.0, self.size.1 as f32 / 2.0),
-                scale: Point::new(self.size.0 as f32, self.size.1 as f32),
-                rotation: 0.0,
-            };
+            let transform = self.root_transform();
+            self.measure(key);
             self.element_transform(key, &transform);
         }
     }
@@ -166,7 +519,7 @@ where
                 for EventListener { msg, .. } in listeners {
                     self.events.events.push(events::Event {
                         event_type: EventTypes::Select,
-                        window_event: WindowEvent::SelectNext,
+                        window_event: Some(WindowEvent::SelectNext),
                         element_event: ElementEvent::Unselect,
                         msg: msg.clone(),
                         key: selected,
@@ -178,7 +531,7 @@ where
         self.select.selected = Some(key);
         self.events.events.push(events::Event {
             event_type: EventTypes::Select,
-            window_event: WindowEvent::SelectNext,
+            window_event: Some(WindowEvent::SelectNext),
             element_event: ElementEvent::Select,
             msg,
             key,
@@ -196,7 +549,7 @@ where
                             for EventListener { msg, .. } in listeners {
                                 self.events.events.push(events::Event {
                                     event_type: EventTypes::MouseLeave,
-                                    window_event: event.clone(),
+                                    window_event: Some(event.clone()),
                                     element_event: element_event.clone(),
                                     msg,
                                     key,
@@ -215,7 +568,7 @@ where
                             for EventListener { msg, .. } in listeners {
                                 self.events.events.push(events::Event {
                                     event_type: EventTypes::MouseEnter,
-                                    window_event: event.clone(),
+                                    window_event: Some(event.clone()),
                                     element_event: element_event.clone(),
                                     msg,
                                     key,
@@ -237,7 +590,9 @@ where
             } else {
                 continue;
             };
-            if element.transform.point_collision(self.input.mouse) {
+            if element.transform.point_collision(self.input.mouse)
+                && self.unclipped(*key, self.input.mouse)
+            {
                 return Some(*key);
             }
         }
@@ -250,9 +605,13 @@ where
                 WindowEvent::MouseMove { position, .. } => {
                     self.input.prev_mouse = self.input.mouse;
                     self.input.mouse = *position;
+                    self.drag_scrollbar();
 
                     self.fix_hovers(&event)
                 }
+                WindowEvent::MouseDown { .. } => self.grab_scrollbar(),
+                WindowEvent::MouseUp { .. } => self.input.drag = None,
+                WindowEvent::Scroll { delta } => self.scroll_wheel(*delta),
                 WindowEvent::SelectNext => {
                     match &self.select.selected {
                         Some(selected) => {
@@ -275,7 +634,7 @@ where
                                         for EventListener { msg, .. } in listeners {
                                             self.events.events.push(events::Event {
                                                 event_type: EventTypes::Select,
-                                                window_event: WindowEvent::SelectNext,
+                                                window_event: Some(WindowEvent::SelectNext),
                                                 element_event: ElementEvent::Unselect,
                                                 msg,
                                                 key: *selected,
@@ -286,7 +645,7 @@ where
                                         for EventListener { msg, .. } in listeners {
                                             self.events.events.push(events::Event {
                                                 event_type: EventTypes::Select,
-                                                window_event: WindowEvent::SelectNext,
+                                                window_event: Some(WindowEvent::SelectNext),
                                                 element_event: ElementEvent::Unselect,
                                                 msg,
                                                 key: *selected,
@@ -306,7 +665,7 @@ where
                                         for EventListener { msg, .. } in listeners {
                                             self.events.events.push(events::Event {
                                                 event_type: EventTypes::Select,
-                                                window_event: WindowEvent::SelectNext,
+                                                window_event: Some(WindowEvent::SelectNext),
                                                 element_event: ElementEvent::Select,
                                                 msg,
                                                 key: self.select.selectables[i + 1],
@@ -319,7 +678,7 @@ where
                                         for EventListener { msg, .. } in listeners {
                                             self.events.events.push(events::Event {
                                                 event_type: EventTypes::Select,
-                                                window_event: WindowEvent::SelectNext,
+                                                window_event: Some(WindowEvent::SelectNext),
                                                 element_event: ElementEvent::Select,
                                                 msg,
                                                 key: *selected,
@@ -338,7 +697,7 @@ where
                                         for EventListener { msg, .. } in listeners {
                                             self.events.events.push(events::Event {
                                                 event_type: EventTypes::Select,
-                                                window_event: WindowEvent::SelectNext,
+                                                window_event: Some(WindowEvent::SelectNext),
                                                 element_event: ElementEvent::Select,
                                                 msg,
                                                 key: *key,
@@ -349,7 +708,7 @@ where
                                         for EventListener { msg, .. } in listeners {
                                             self.events.events.push(events::Event {
                                                 event_type: EventTypes::Select,
-                                                window_event: WindowEvent::SelectNext,
+                                                window_event: Some(WindowEvent::SelectNext),
                                                 element_event: ElementEvent::Unselect,
                                                 msg,
                                                 key: *selected,
@@ -374,7 +733,7 @@ where
                                 for EventListener { msg, .. } in listeners {
                                     self.events.events.push(events::Event {
                                         event_type: EventTypes::Select,
-                                        window_event: WindowEvent::SelectNext,
+                                        window_event: Some(WindowEvent::SelectNext),
                                         element_event: ElementEvent::Select,
                                         msg,
                                         key: *key,
@@ -398,7 +757,7 @@ where
                                 for EventListener { msg, .. } in e {
                                     self.events.events.push(events::Event {
                                         event_type: EventTypes::Input,
-                                        window_event: event.clone(),
+                                        window_event: Some(event.clone()),
                                         element_event: ElementEvent::Input { text: text.clone() },
                                         msg,
                                         key,
@@ -431,7 +790,9 @@ where
                         match element.events.get(&event_type) {
                             Some(listeners) => {
                                 let position = self.input.mouse;
-                                if element.transform.point_collision(position) {
+                                if element.transform.point_collision(position)
+                                    && self.unclipped(self.ordered[i], position)
+                                {
                                     let element_event = ElementEvent::from_window_event(
                                         &event,
                                         element,
@@ -448,7 +809,7 @@ where
                                                 }
                                                 self.events.events.push(events::Event {
                                                     event_type,
-                                                    window_event: event.clone(),
+                                                    window_event: Some(event.clone()),
                                                     element_event: element_event.clone(),
                                                     msg: msg.clone(),
                                                     key: self.ordered[i],
@@ -461,7 +822,7 @@ where
                                                 }
                                                 self.events.events.push(events::Event {
                                                     event_type,
-                                                    window_event: event.clone(),
+                                                    window_event: Some(event.clone()),
                                                     element_event: element_event.clone(),
                                                     msg: msg.clone(),
                                                     key: self.ordered[i],
@@ -470,7 +831,7 @@ where
                                             EventListenerTypes::Force => {
                                                 self.events.events.push(events::Event {
                                                     event_type,
-                                                    window_event: event.clone(),
+                                                    window_event: Some(event.clone()),
                                                     element_event: element_event.clone(),
                                                     msg: msg.clone(),
                                                     key: self.ordered[i],
@@ -493,9 +854,18 @@ where
     }
 
     pub fn resize(&mut self, size: (u32, u32), queue: &wgpu::Queue) {
+        self.set_size(size);
+        if let Some(gpu) = &mut self.gpu {
+            gpu.resize(size, queue);
+        }
+    }
+
+    /// Resizes the `Gui` without touching the GPU
+    ///
+    /// GPU resources catch up with the new size on the next `prepare`
+    pub fn set_size(&mut self, size: (u32, u32)) {
         self.resolve_events();
         self.size = size;
-        self.gpu.resize((size.0, size.1), queue);
         let entry_key = if let Some(entry) = &self.entry {
             entry
         } else {
@@ -504,14 +874,9 @@ where
         /*if let Some(entry) = self.elements.get_mut(&entry_key) {
             entry.styles.flags.recalc_transform = true;
         }*/
-        self.element_transform(
-            *entry_key,
-            &ElementTransform {
-                position: Point::new(size.0 as f32 / 2.0, size.1 as f32 / 2.0),
-                scale: Point::new(size.0 as f32, size.1 as f32),
-                rotation: 0.0,
-            },
-        );
+        let entry_key = *entry_key;
+        self.measure(entry_key);
+        self.element_transform(entry_key, &self.root_transform());
     }
 
     pub fn update(&mut self) {
@@ -521,8 +886,86 @@ where
         } else {
             return;
         };
+        let under_mouse = self.under_mouse();
+        self.order_entry(entry_key);
+        self.measure(entry_key);
+        self.element_transform(entry_key, &self.root_transform());
+        self.resolve_references();
+        if self.rows_changed {
+            self.rows_changed = false;
+            self.order_entry(entry_key);
+        }
+        for key in &self.ordered {
+            if let Some(element) = self.elements.get_mut(*key) {
+                element.sync_render_data();
+            }
+        }
+        self.moved_under_mouse(&under_mouse);
+    }
+
+    /// Returns drawn elements listening to `MouseEnter` or `MouseLeave` and
+    /// whether the mouse is over them
+    ///
+    /// Reads the geometry of the last `update`, clips included
+    fn under_mouse(&self) -> HashMap<ElementKey, bool> {
+        let mouse = self.input.mouse;
+        self.ordered
+            .iter()
+            .filter_map(|key| Some((*key, self.elements.get(*key)?)))
+            .filter(|(_, element)| {
+                element.events.events.iter().any(|listener| {
+                    matches!(
+                        listener.event_type,
+                        EventTypes::MouseEnter | EventTypes::MouseLeave
+                    )
+                })
+            })
+            .map(|(key, element)| {
+                let over = element.render_element.1.point_collision(mouse)
+                    && self.unclipped(key, mouse);
+                (key, over)
+            })
+            .collect()
+    }
+
+    /// Sends `MouseEnter` and `MouseLeave` to elements that layout moved
+    /// under or away from the mouse
+    fn moved_under_mouse(&mut self, before: &HashMap<ElementKey, bool>) {
+        for (key, over) in self.under_mouse() {
+            let was_over = before.get(&key).copied().unwrap_or(false);
+            let event_type = match (was_over, over) {
+                (true, false) => EventTypes::MouseLeave,
+                (false, true) => EventTypes::MouseEnter,
+                _ => continue,
+            };
+            let element = match self.elements.get(key) {
+                Some(element) => element,
+                None => continue,
+            };
+            if let Some(listeners) = element.events.get(&event_type) {
+                let event = WindowEvent::MouseMove {
+                    position: self.input.mouse,
+                    last: self.input.prev_mouse,
+                };
+                let element_event = ElementEvent::from_window_event(&event, element, &self.input);
+                for EventListener { msg, .. } in listeners {
+                    self.events.events.push(events::Event {
+                        event_type,
+                        window_event: Some(event.clone()),
+                        element_event: element_event.clone(),
+                        msg,
+                        key,
+                    });
+                }
+            }
+        }
+    }
+
+    /// Collects elements that will be drawn, back to front
+    fn order_entry(&mut self, entry_key: ElementKey) {
         self.ordered.clear();
         self.select.selectables.clear();
+        self.stale_keys.clear();
         self.order(entry_key);
         let mut ordered = self.ordered.clone();
         ordered.sort_by(|a, b| {
@@ -532,14 +975,6 @@ where
                 .cmp(&self.get_element(*b).map(|e| e.styles.z_index).unwrap_or(0))
         });
         self.ordered = ordered;
-        self.element_transform(
-            entry_key,
-            &ElementTransform {
-                position: Point::new(self.size.0 as f32 / 2.0, self.size.1 as f32 / 2.0),
-                scale: Point::new(self.size.0 as f32, self.size.1 as f32),
-                rotation: 0.0,
-            },
-        );
     }
 
     fn order(&mut self, key: ElementKey) {
@@ -549,6 +984,9 @@ where
             }
             element
         } else {
+            if self.is_stale(key) {
+                self.stale_keys.push(key);
+            }
             return;
         };
         if element.styles.selectable {
@@ -560,41 +998,24 @@ where
         } else {
             return;
         };
-        match &element.children {
-            Children::Element(key) => self.order(*key),
-            Children::Layers(layers) => {
-                let keys = layers.clone();
-                for key in keys {
-                    self.order(key);
-                }
-            }
-            Children::Rows {
-                children,
-                spacing: _,
-            } => {
-                let keys = children.clone();
-                for Section { element, .. } in keys {
-                    self.order(element);
-                }
-            }
-            Children::Columns {
-                children,
-                spacing: _,
-            } => {
-                let keys = children.clone();
-                for Section { element, .. } in keys {
-                    self.order(element);
-                }
-            }
-            Children::None => (),
+        for child in element.children.keys() {
+            self.order(child);
         }
     }
 
     pub fn prepare(&mut self, device: &wgpu::Device, queue: &wgpu::Queue) {
+        match &mut self.gpu {
+            Some(gpu) => {
+                if gpu.size != self.size {
+                    gpu.resize(self.size, queue);
+                }
+            }
+            None => self.gpu = Some(GpuBound::new(queue, device, self.size, self.stencil)),
+        }
         let mut font = self.font_system.take().unwrap();
         let mut swash = self.swash_cache.take().unwrap();
         for i in 0..self.ordered.len() {
-            let e = if let Some(e) = self.get_element_mut(self.ordered[i]) {
+            let e = if let Some(e) = self.elements.get_mut(self.ordered[i]) {
                 e
             } else {
                 continue;
@@ -606,23 +1027,43 @@ where
     }
 
     fn element_transform(&mut self, key: ElementKey, transform: &ElementTransform) {
-        let (container, view_port) = (
+        self.sized_element_transform(key, transform, None);
+    }
+
+    /// Lays out the element inside `transform`
+    ///
+    /// If `size` is set, it is used instead of the element's width and height,
+    /// layouts that size their children themselves use it to avoid resolving
+    /// relative values twice.
+    fn sized_element_transform(
+        &mut self,
+        key: ElementKey,
+        transform: &ElementTransform,
+        size: Option<Point>,
+    ) {
+        let (mut container, view_port): (styles::Container, _) = (
             transform.clone().into(),
-            ViewPort(self.size.0 as f32, self.size.1 as f32),
+            self.view_port(),
         );
-        let element = match self.elements.get_mut(&key) {
+        container.elements = self.referenced(key);
+        let element = match self.elements.get_mut(key) {
             Some(element) => element,
             None => return,
         };
+        element.slot = Some((transform.clone(), size));
+        element.intrinsic.apply(&mut container);
         if true {
-            let (width, height) = (
-                element
-                    .styles
-                    .width.get().calc(&container, &view_port),
-                element
-                    .styles
-                    .height.get().calc(&container, &view_port),
-            );
+            let (width, height) = match size {
+                Some(size) => (size.x, size.y),
+                None => {
+                    let size = Point::new(
+                        element.styles.width.get().calc(&container, &view_port),
+                        element.styles.height.get().calc(&container, &view_port),
+                    );
+                    let size = element.styles.clamp_size(size, &container, &view_port);
+                    (size.x, size.y)
+                }
+            };
             let pos = 
                 element
                     .styles
@@ -636,63 +1077,48 @@ where
                 .get()
                 .calc(&container, &view_port);
             let margin = element.styles.margin.get().calc(&container, &view_port);
-            let transform = ElementTransform {
+            // the pivot sits on the position and the element rotates around it
+            let (pivot_x, pivot_y) = *element.styles.pivot.get();
+            let pos = rotate_point(
+                Point::new(
+                    pos.x + (0.5 - pivot_x) * width,
+                    pos.y + (0.5 - pivot_y) * height,
+                ),
+                pos,
+                rotation,
+            );
+            let mut transform = ElementTransform {
                 position: pos,
-                scale: Point::new((width-margin).max(0.0), (height-margin).max(0.0)),
+                scale: Point::new(width, height),
                 rotation,
-            };
+                matrix: transform.matrix,
+            }
+            .inset(&margin);
+            let local = element.styles.transform.get();
+            if !local.ops.is_empty() {
+                let own = styles::Container {
+                    elements: container.elements.clone(),
+                    ..transform.clone().into()
+                };
+                let (origin_x, origin_y) = local.origin.unwrap_or((pivot_x, pivot_y));
+                let origin = rotate_point(
+                    Point::new(
+                        transform.position.x + (origin_x - 0.5) * transform.scale.x,
+                        transform.position.y + (origin_y - 0.5) * transform.scale.y,
+                    ),
+                    transform.position,
+                    transform.rotation,
+                );
+                transform.matrix =
+                    transform.matrix * local.calc(&own, &view_port).around(origin);
+            }
 
-            let pre_collision = element.transform.point_collision(self.input.mouse);
             element.transform = transform;
-            
-
-            let post_collision = element.transform.point_collision(self.input.mouse);
-            match (pre_collision, post_collision) {
-                (true, false) => {
-                    if let Some(listeners) = element.events.get(&EventTypes::MouseLeave) {
-                        for EventListener { msg, .. } in listeners {
-                            let event = WindowEvent::MouseMove {
-                                position: self.input.mouse,
-                                last: self.input.prev_mouse,
-                            };
-                            self.events.events.push(events::Event {
-                                event_type: EventTypes::MouseLeave,
-                                element_event: ElementEvent::from_window_event(
-                                    &event,
-                                    &element,
-                                    &self.input,
-                                ),
-                                window_event: event,
-                                msg: msg.clone(),
-                                key,
-                            });
-                        }
-                    }
-                }
-                (false, true) => {
-                    if let Some(listeners) = element.events.get(&EventTypes::MouseEnter) {
-                        for EventListener { msg, .. } in listeners {
-                            let event = WindowEvent::MouseMove {
-                                position: self.input.mouse,
-                                last: self.input.prev_mouse,
-                            };
-                            self.events.events.push(events::Event {
-                                event_type: EventTypes::MouseEnter,
-                                element_event: ElementEvent::from_window_event(
-                                    &event,
-                                    &element,
-                                    &self.input,
-                                ),
-                                window_event: event,
-                                msg: msg.clone(),
-                                key,
-                            });
-                        }
-                    }
-                }
-                _ => {}
-            }
-            let container = element.transform.clone().into();
+            let mut container = styles::Container {
+                elements: std::mem::take(&mut container.elements),
+                ..element.transform.clone().into()
+            };
+            element.intrinsic.apply(&mut container);
 
 
             let edges_radius = element.styles.edges_radius.get().calc(&container, &view_port);
@@ -757,109 +1183,52 @@ where
                 }
             }
         }
-        let transform = &element.transform;
+        let padding = element.styles.padding.get().calc(&container, &view_port);
+        let transform = element.transform.inset(&padding);
         match element.children.to_owned() {
             Children::Element(child) => {
-                let padding = element.styles.padding.get().calc(&container, &view_port);
-                let transform = ElementTransform {
-                    scale: Point::new(
-                        transform.scale.x - padding,
-                        transform.scale.y - padding,
-                    ),
-                    ..transform.clone()
-                };
-                self.element_transform(child.clone(), &transform);
-                return;
+                self.element_transform(child, &transform);
             }
             Children::Layers(children) => {
-                let padding = element.styles.padding.get().calc(&container, &view_port);
-                let transform = ElementTransform {
-                    scale: Point::new(
-                        transform.scale.x - padding,
-                        transform.scale.y - padding,
-                    ),
-                    ..transform.clone()
-                };
                 for child in children {
                     self.element_transform(child, &transform);
                 }
             }
-            Children::Rows { children, .. } => {
-                if children.is_empty() {
-                    return;
-                }
-                let mut len = children.len() as f32;
-                let mut remaining_height = transform.scale.y;
-                let mut y = transform.position.y - transform.scale.y / 2.0;
-                let transform = element.transform.clone();
-                for Section {
-                    element,
-                    size: spacing,
-                } in children
-                {
-                    if remaining_height <= 0.0 {
-                        break;
-                    }
-                    let space = match spacing {
-                        Some(spacing) => spacing.calc(&container, &view_port),
-                        None => remaining_height / len,
-                    };
-                    let position = if transform.rotation == 0.0 {
-                        Point::new(transform.position.x, y + space / 2.0)
-                    } else {
-                        let pivot = transform.position;
-                        let point = Point::new(transform.position.x, y + space / 2.0);
-                        rotate_point(point, pivot, transform.rotation)
-                    };
-                    let transform = ElementTransform {
-                        position,
-                        scale: Point::new(transform.scale.x, space),
-                        rotation: transform.rotation,
-                    };
-                    y += space;
-                    remaining_height -= space;
-                    len -= 1.0;
-                    self.element_transform(element, &transform);
-                }
+            Children::Rows {
+                children,
+                spacing,
+                padding,
+                align,
+            } => {
+                self.sections_transform(
+                    &children,
+                    spacing.as_ref(),
+                    padding.as_ref(),
+                    align,
+                    &transform,
+                    true,
+                );
             }
-            Children::Columns { children, .. } => {
-                if children.is_empty() {
-                    return;
-                }
-                let mut len = children.len() as f32;
-                let mut remaining_width = transform.scale.x;
-                let mut x = transform.position.x - transform.scale.x / 2.0;
-                let transform = element.transform.clone();
-                for Section {
-                    element,
-                    size: spacing,
-                } in children
-                {
-                    if remaining_width <= 0.0 {
-                        break;
-                    }
-                    let space = match spacing {
-                        Some(spacing) => spacing.calc(&container, &view_port),
-                        None => remaining_width / len,
-                    };
-                    let position = if transform.rotation == 0.0 {
-                        Point::new(x + space / 2.0, transform.position.y)
-                    } else {
-                        let pivot = transform.position;
-                        let point = Point::new(x + space / 2.0, transform.position.y);
-                        rotate_point(point, pivot, transform.rotation)
-                    };
-                    let transform = ElementTransform {
-                        position,
-                        scale: Point::new(space, transform.scale.y),
-                        rotation: transform.rotation,
-                    };
-                    self.element_transform(element, &transform);
-                    x += space;
-                    remaining_width -= space;
-                    len -= 1.0;
-                }
+            Children::Columns {
+                children,
+                spacing,
+                padding,
+                align,
+            } => {
+                self.sections_transform(
+                    &children,
+                    spacing.as_ref(),
+                    padding.as_ref(),
+                    align,
+                    &transform,
+                    false,
+                );
             }
+            Children::Flex(flex) => self.flex_transform(&flex, &transform),
+            Children::Grid(grid) => self.grid_transform(&grid, &transform),
+            Children::Constraints(layout) => self.constraints_transform(key, layout, &transform),
+            Children::Scroll(scroll) => self.scroll_transform(key, scroll, &transform),
+            Children::List(list) => self.list_transform(key, list, &transform),
             Children::None => (),
         };
     }
@@ -868,14 +1237,93 @@ where
         self.size
     }
 
+    /// Returns where the element ended up after the last `update`
+    pub fn element_rect(&self, key: ElementKey) -> Option<ElementRect> {
+        self.get_element(key).map(|element| element.transform.rect())
+    }
+
+    /// Returns all visible elements under the point, topmost first
+    pub fn hit_test(&self, point: Point) -> Vec<ElementKey> {
+        self.ordered
+            .iter()
+            .rev()
+            .copied()
+            .filter(|key| {
+                self.get_element(*key)
+                    .is_some_and(|element| element.transform.point_collision(point))
+                    && self.unclipped(*key, point)
+            })
+            .collect()
+    }
+
     pub fn render<'a>(&'a self, pass: &mut wgpu::RenderPass<'a>) {
-        pass.set_bind_group(0, &self.gpu.dimensions_bind_group, &[]);
+        let gpu = match &self.gpu {
+            Some(gpu) => gpu,
+            None => return,
+        };
+        pass.set_bind_group(0, &gpu.dimensions_bind_group, &[]);
 
-        for e in self.ordered.iter() {
-            if let Some(e) = self.get_element(*e) {
-                if let Some(re) = &e.render_element.0 {
-                    re.render(&self.gpu.pipelines, pass)
+        let (width, height) = gpu.size;
+        let scissor = |pass: &mut wgpu::RenderPass<'a>, rect: Option<&styles::Rectangle>| {
+            let rect = match rect {
+                Some(rect) => rect,
+                None => return pass.set_scissor_rect(0, 0, width, height),
+            };
+            let x = (rect.position.x.max(0.0) as u32).min(width);
+            let y = (rect.position.y.max(0.0) as u32).min(height);
+            let right = ((rect.position.x + rect.size.x).ceil().max(0.0) as u32).clamp(x, width);
+            let bottom = ((rect.position.y + rect.size.y).ceil().max(0.0) as u32).clamp(y, height);
+            pass.set_scissor_rect(x, y, right - x, bottom - y);
+        };
+        // scissor rects and elements drawn into the stencil, the stencil
+        // reference is the number of stencil clips
+        let mut clips: Vec<(styles::Rectangle, Option<&render::RenderElement>)> = Vec::new();
+        if let Some(letterbox) = self.letterbox() {
+            clips.push((letterbox, None));
+        }
+        scissor(pass, clips.last().map(|(clip, _)| clip));
+        let mut depth = 0;
+        if gpu.pipelines.clip_push_pipeline.is_some() {
+            pass.set_stencil_reference(depth);
+        }
+        for command in self.display_list() {
+            let key = match &command {
+                DrawCommand::Quad { key, .. } | DrawCommand::Text { key, .. } => *key,
+                DrawCommand::PushClip { key, data } => {
+                    let rect = display_list::bounds(data);
+                    let rect = match clips.last() {
+                        Some((clip, _)) => display_list::intersect(clip, &rect),
+                        None => rect,
+                    };
+                    scissor(pass, Some(&rect));
+                    let mask = match &gpu.pipelines.clip_push_pipeline {
+                        Some(pipeline) if display_list::needs_stencil(data) => self
+                            .get_element(*key)
+                            .and_then(|e| e.render_element.0.as_ref())
+                            .map(|re| (pipeline, re)),
+                        _ => None,
+                    };
+                    if let Some((pipeline, re)) = mask {
+                        re.draw_clip(pipeline, pass);
+                        depth += 1;
+                        pass.set_stencil_reference(depth);
+                    }
+                    clips.push((rect, mask.map(|(_, re)| re)));
+                    continue;
                 }
+                DrawCommand::PopClip => {
+                    let mask = clips.pop().and_then(|(_, mask)| mask);
+                    if let (Some(re), Some(pipeline)) = (mask, &gpu.pipelines.clip_pop_pipeline) {
+                        re.draw_clip(pipeline, pass);
+                        depth -= 1;
+                        pass.set_stencil_reference(depth);
+                    }
+                    scissor(pass, clips.last().map(|(clip, _)| clip));
+                    continue;
+                }
+            };
+            if let Some(re) = self.get_element(key).and_then(|e| e.render_element.0.as_ref()) {
+                re.draw(&command, &gpu.pipelines, pass)
             }
         }
     }
@@ -888,12 +1336,14 @@ where
 /// when the element is rendered for the first time or when the element
 /// or its parent is resized
 pub(crate) struct ElementTransform {
-    /// Position in x and y of the top left corner
+    /// Position of the center in x and y
     pub position: Point,
     /// Scale in width and height
     pub scale: Point,
     /// Rotation in radians
     pub rotation: f32,
+    /// `transform` styles of the element and its ancestors, applied after layout
+    pub matrix: transform::Matrix,
 }
 
 impl ElementTransform {
@@ -902,10 +1352,79 @@ impl ElementTransform {
             position: Point::new(0.0, 0.0),
             scale: Point::new(0.0, 0.0),
             rotation: 0.0,
+            matrix: transform::Matrix::IDENTITY,
+        }
+    }
+
+    /// Shrinks the transform by `edges` on each side
+    ///
+    /// Edges are measured in the rotated frame of the transform,
+    /// so the center moves along the rotated axes.
+    pub fn inset(&self, edges: &styles::Edges<f32>) -> Self {
+        let offset = rotate_point(
+            Point::new(
+                (edges.left - edges.right) / 2.0,
+                (edges.top - edges.bottom) / 2.0,
+            ),
+            Point::new(0.0, 0.0),
+            self.rotation,
+        );
+        Self {
+            position: Point::new(self.position.x + offset.x, self.position.y + offset.y),
+            scale: Point::new(
+                (self.scale.x - edges.horizontal()).max(0.0),
+                (self.scale.y - edges.vertical()).max(0.0),
+            ),
+            rotation: self.rotation,
+            matrix: self.matrix,
+        }
+    }
+
+    pub fn rect(&self) -> ElementRect {
+        let (half_width, half_height) = (self.scale.x / 2.0, self.scale.y / 2.0);
+        let corners = [
+            Point::new(-half_width, -half_height),
+            Point::new(half_width, -half_height),
+            Point::new(half_width, half_height),
+            Point::new(-half_width, half_height),
+        ]
+        .map(|corner| {
+            let corner = rotate_point(corner, Point::new(0.0, 0.0), self.rotation);
+            self.matrix.apply(Point::new(
+                self.position.x + corner.x,
+                self.position.y + corner.y,
+            ))
+        });
+        let (mut min, mut max) = (corners[0], corners[0]);
+        for corner in &corners[1..] {
+            min = Point::new(min.x.min(corner.x), min.y.min(corner.y));
+            max = Point::new(max.x.max(corner.x), max.y.max(corner.y));
+        }
+        ElementRect {
+            center: self.position,
+            size: self.scale,
+            rotation: self.rotation,
+            transform: self.matrix,
+            bounds: styles::Rectangle {
+                position: min,
+                size: Point::new(max.x - min.x, max.y - min.y),
+            },
+        }
+    }
+
+    /// Returns the point in layout coordinates, before the `transform` styles
+    pub fn untransform(&self, point: Point) -> Option<Point> {
+        match self.matrix.is_identity() {
+            true => Some(point),
+            false => self.matrix.inverse().map(|inverse| inverse.apply(point)),
         }
     }
 
     pub fn point_collision(&self, point: Point) -> bool {
+        let point = match self.untransform(point) {
+            Some(point) => point,
+            None => return false,
+        };
         let point_rotated = rotate_point(point, self.position, -self.rotation);
         let width = self.scale.x / 2.0;
         let height = self.scale.y / 2.0;
@@ -921,6 +1440,37 @@ impl ElementTransform {
     }
 }
 
+/// Resolved geometry of an `Element`
+///
+/// Returned by `Gui::element_rect`
+#[derive(Debug, Clone, Copy)]
+pub struct ElementRect {
+    /// Center of the element
+    pub center: Point,
+    /// Width and height of the element before rotation
+    pub size: Point,
+    /// Rotation in radians around the center
+    pub rotation: f32,
+    /// `transform` styles of the element and its ancestors, applied on top of
+    /// the center, size and rotation
+    pub transform: transform::Matrix,
+    /// Axis-aligned box around the rotated element, `position` is its top left corner
+    pub bounds: styles::Rectangle,
+}
+
+impl ElementRect {
+    /// Returns true if the point lies inside the rotated element
+    pub fn contains(&self, point: Point) -> bool {
+        ElementTransform {
+            position: self.center,
+            scale: self.size,
+            rotation: self.rotation,
+            matrix: self.transform,
+        }
+        .point_collision(point)
+    }
+}
+
 /// Most basic building block of the Rugui library
 #[derive(Default)]
 pub struct Element<Msg>
@@ -928,14 +1478,18 @@ where
     Msg: Clone,
 {
     text: Option<(String, bool)>,
-    pub label: Option<String>,
+    /// Set with `with_label` or `Gui::set_label`, which keeps the label index in sync
+    label: Option<String>,
     pub render_element: (Option<RenderElement>, RenderElementData),
     pub styles: styles::Styles,
     pub events: EventListeners<Msg>,
     pub children: Children,
     text_buffer: Option<cosmic_text::Buffer>,
     transform: ElementTransform,
-    _parent: ElementTransform,
+    intrinsic: measure::Intrinsic,
+    parent: Option<ElementKey>,
+    /// Space and size the element was last laid out with
+    slot: Option<(ElementTransform, Option<Point>)>,
 }
 
 /// Holds all event listeners for an `Element`
@@ -1043,16 +1597,24 @@ where
             children: Children::None,
             text_buffer: None,
             transform: ElementTransform::zeroed(),
-            _parent: ElementTransform::zeroed(),
+            intrinsic: measure::Intrinsic::default(),
+            parent: None,
+            slot: None,
         }
     }
 
     /// Configures label for `Element`
+    ///
+    /// Use `Gui::set_label` to change it once the element is added
     pub fn with_label(mut self, label: &str) -> Self {
         self.label = Some(label.to_string());
         self
     }
 
+    pub fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
     /// Configures styles for `Element`
     pub fn with_styles(mut self, styles: styles::Styles) -> Self {
         self.styles = styles;
@@ -1080,6 +1642,13 @@ where
         self
     }
 
+    /// Updates render data that doesn't need the GPU
+    pub(crate) fn sync_render_data(&mut self) {
+        self.render_element.1.color = self.styles.bg_color.get().to_rgba().into();
+        self.render_element.1.alpha = *self.styles.alpha.get();
+        self.render_element.1.update_transform(&self.transform);
+    }
+
     pub(crate) fn write(
         &mut self,
         device: &wgpu::Device,
@@ -1091,9 +1660,9 @@ where
             self.render_element.0 = Some(RenderElement::zeroed(device))
         }
         let mut render_element = self.render_element.0.take().unwrap();
-        self.render_element.1.color = self.styles.bg_color.get().to_rgba().into();
         if self.styles.bg_texture.dirty {
             if let Some(texture) = &self.styles.bg_texture.get() {
+                texture.upload(device, queue);
                 render_element.set_texture(texture.clone());
             }
 
@@ -1127,14 +1696,8 @@ where
             );
 
         }
-        if self.styles.alpha.dirty {
-            let alpha = *self.styles.alpha.get();
-            self.render_element.1.alpha = alpha;
-            self.styles.alpha.dirty = false;
-        }
+        self.styles.alpha.dirty = false;
         //if self.styles.flags.dirty_transform {
-            let transform = &self.transform;
-            self.render_element.1.update_transform(transform);
             if let Some((_, flag)) = &mut self.text {
                 *flag = true;
             }
@@ -1182,74 +1745,16 @@ where
                 }
             }
         }
-        match &mut self.text {
-            Some((txt, dirty)) => {
-                if *dirty && transform.scale.x > 0.0 && transform.scale.y > 0.0 {
-                    match &mut self.text_buffer {
-                        Some(tb) => {
-                            let mut tb = tb.borrow_with(font_system);
-                            tb.set_metrics(Metrics::new(
-                                self.render_element.1.text_size,
-                                self.render_element.1.text_size+3.0,
-                            ));
-                            tb.set_size(Some(self.transform.scale.x), Some(self.transform.scale.y));
-                            let attrs = Attrs::new();
-                            tb.set_text(&txt, attrs, cosmic_text::Shaping::Advanced);
-                            let color = self.styles.text_color.get().to_rgba();
-                            let mut image = DynamicImage::new(
-                                self.transform.scale.x as u32,
-                                self.transform.scale.y as u32,
-                                image::ColorType::Rgba8,
-                            );
-                            tb.draw(swash_cache, cosmic_text::Color::rgba((color.0 * 255.0) as u8, (color.1 * 255.0) as u8, (color.2 * 255.0) as u8, (color.3 * 255.0) as u8), |x, y, _, _, color| {
-                                if x < 0
-                                    || y < 0
-                                    || x >= self.transform.scale.x as i32
-                                    || y >= self.transform.scale.y as i32
-                                {
-                                    return;
-                                }
-                                image.put_pixel(x as u32, y as u32, color.as_rgba().into())
-                            });
-                            self.text_buffer = Some(tb.clone());
-                            let tex = texture::Texture::from_image(device, queue, &image, None);
-                            render_element.text = Some(tex)
-                        }
-                        None => {
-                            let mut tb = cosmic_text::Buffer::new(
-                                font_system,
-                                Metrics::new(
-                                    self.render_element.1.text_size,
-                                    self.render_element.1.text_size+3.0,
-                                ),
-                            );
-                            let mut tb = tb.borrow_with(font_system);
-                            tb.set_size(Some(self.transform.scale.x), Some(self.transform.scale.y));
-                            let attrs = Attrs::new();
-                            tb.set_text(&txt, attrs, cosmic_text::Shaping::Advanced);
-                            tb.shape_until_scroll(true);
-                            let color = self.styles.text_color.get().to_rgba();
-                            let mut image = DynamicImage::new(
-                                self.transform.scale.x as u32,
-                                self.transform.scale.y as u32,
-                                image::ColorType::Rgba8,
-                            );
-                            tb.draw(swash_cache, cosmic_text::Color::rgba((color.0 * 255.0) as u8, (color.1 * 255.0) as u8, (color.2 * 255.0) as u8, (color.3 * 255.0) as u8), |x, y, _, _, color| {
-                                if x < 0
-                                    || y < 0
-                                    || x >= self.transform.scale.x as i32
-                                    || y >= self.transform.scale.y as i32
-                                {
-                                    return;
-                                }
-                                image.put_pixel(x as u32, y as u32, color.as_rgba().into())
-                            });
-                            self.text_buffer = Some(tb.clone());
-                            let tex = texture::Texture::from_image(device, queue, &image, None);
-                            render_element.text = Some(tex)
-                        }
+        match &self.text {
+            Some((_, dirty)) => {
+                if *dirty {
+                    if let Some(image) = self.rasterize_text(font_system, swash_cache) {
+                        let tex = texture::Texture::from_image(device, queue, &image, None);
+                        render_element.text = Some(tex)
+                    }
+                    if let Some((_, dirty)) = &mut self.text {
+                        *dirty = false;
                     }
-                    *dirty = false;
                 }
             }
             None => render_element.text = None
@@ -1259,6 +1764,43 @@ where
         self.render_element.0 = Some(render_element)
     }
 
+    /// Draws text of the element into an image the size of the element
+    pub(crate) fn rasterize_text(
+        &mut self,
+        font_system: &mut FontSystem,
+        swash_cache: &mut SwashCache,
+    ) -> Option<DynamicImage> {
+        let txt = match &self.text {
+            Some((txt, _)) => txt,
+            None => return None,
+        };
+        let (width, height) = (self.transform.scale.x, self.transform.scale.y);
+        if width <= 0.0 || height <= 0.0 {
+            return None;
+        }
+        let metrics = Metrics::new(
+            self.render_element.1.text_size,
+            self.render_element.1.text_size + 3.0,
+        );
+        let tb = self
+            .text_buffer
+            .get_or_insert_with(|| cosmic_text::Buffer::new(font_system, metrics));
+        let mut tb = tb.borrow_with(font_system);
+        tb.set_metrics(metrics);
+        tb.set_size(Some(width), Some(height));
+        tb.set_text(txt, Attrs::new(), cosmic_text::Shaping::Advanced);
+        tb.shape_until_scroll(true);
+        let color = self.styles.text_color.get().to_rgba();
+        let mut image = DynamicImage::new(width as u32, height as u32, image::ColorType::Rgba8);
+        tb.draw(swash_cache, cosmic_text::Color::rgba((color.0 * 255.0) as u8, (color.1 * 255.0) as u8, (color.2 * 255.0) as u8, (color.3 * 255.0) as u8), |x, y, _, _, color| {
+            if x < 0 || y < 0 || x >= width as i32 || y >= height as i32 {
+                return;
+            }
+            image.put_pixel(x as u32, y as u32, color.as_rgba().into())
+        });
+        Some(image)
+    }
+
     /// Returns text rendered inside the `Element`
     pub fn text(&self) -> Option<&String> {
         match &self.text {
@@ -1269,6 +1811,7 @@ where
 
     /// Configures text rendered inside the `Element`
     pub fn set_text(&mut self, text: Option<String>) {
+        self.intrinsic.text = None;
         match text {
             Some(text) => self.text = Some((text, true)),
             None => self.text = None,
@@ -1277,6 +1820,7 @@ where
 
     /// Configures text rendered inside the `Element`
     pub fn text_str(&mut self, str: &str) {
+        self.intrinsic.text = None;
         match &mut self.text {
             Some((text, dirty)) => {
                 *dirty = true;
@@ -1290,6 +1834,7 @@ where
 
     /// Configures text rendered inside the `Element`
     pub fn text_string(&mut self, str: String) {
+        self.intrinsic.text = None;
         match &mut self.text {
             Some((text, dirty)) => {
                 *dirty = true;
@@ -1302,6 +1847,7 @@ where
     }
 
     pub(crate) fn place_point(&self, point: Point) -> Point {
+        let point = self.transform.untransform(point).unwrap_or(point);
         let x = point.x - self.transform.position.x;
         let y = point.y - self.transform.position.y;
         let point = Point::new(x, y);
@@ -1319,19 +1865,93 @@ pub enum Children {
     /// Positions child `Elements` in rows on top of the parent
     Rows {
         children: Vec<Section>,
+        /// Space between rows
         spacing: Option<Values>,
+        /// Space before the first and after the last row
+        padding: Option<Values>,
+        /// Horizontal placement of children, with `Align::Stretch` each row spans the whole width
+        align: layout::Align,
     },
     /// Positions child `Elements` in columns on top of the parent
     Columns {
         children: Vec<Section>,
+        /// Space between columns
         spacing: Option<Values>,
+        /// Space before the first and after the last column
+        padding: Option<Values>,
+        /// Vertical placement of children, with `Align::Stretch` each column spans the whole height
+        align: layout::Align,
     },
+    /// Positions child `Elements` along a line that can grow, shrink and wrap
+    Flex(layout::Flex),
+    /// Positions child `Elements` in cells of a table
+    Grid(layout::Grid),
+    /// Positions child `Elements` by constraints between their edges, centers and sizes
+    Constraints(constraints::ConstraintLayout),
+    /// Shows part of a child `Element` that is bigger than the parent
+    Scroll(scroll::Scroll),
+    /// Positions rows that are only created when visible
+    List(list::VirtualList),
 
     /// Element has no children
     #[default]
     None,
 }
 
+impl Children {
+    /// Returns keys of all child `Elements` in order
+    pub fn keys(&self) -> Vec<ElementKey> {
+        match self {
+            Children::Element(key) => vec![*key],
+            Children::Layers(layers) => layers.clone(),
+            Children::Rows { children, .. } | Children::Columns { children, .. } => {
+                children.iter().map(|section| section.element).collect()
+            }
+            Children::Flex(flex) => flex.children.iter().map(|item| item.element).collect(),
+            Children::Grid(grid) => grid.children.iter().map(|item| item.element).collect(),
+            Children::Constraints(layout) => layout.children.clone(),
+            Children::Scroll(scroll) => std::iter::once(scroll.content)
+                .chain(scroll.vertical_bar)
+                .chain(scroll.horizontal_bar)
+                .collect(),
+            Children::List(list) => list.keys(),
+            Children::None => Vec::new(),
+        }
+    }
+
+    /// Removes the child `Element` from the container
+    pub fn remove(&mut self, key: ElementKey) {
+        match self {
+            Children::Element(child) => {
+                if *child == key {
+                    *self = Children::None;
+                }
+            }
+            Children::Layers(layers) => layers.retain(|child| *child != key),
+            Children::Rows { children, .. } | Children::Columns { children, .. } => {
+                children.retain(|section| section.element != key)
+            }
+            Children::Flex(flex) => flex.children.retain(|item| item.element != key),
+            Children::Grid(grid) => grid.children.retain(|item| item.element != key),
+            Children::Constraints(layout) => layout.remove(key),
+            Children::Scroll(scroll) => {
+                if scroll.content == key {
+                    *self = Children::None;
+                    return;
+                }
+                if scroll.vertical_bar == Some(key) {
+                    scroll.vertical_bar = None;
+                }
+                if scroll.horizontal_bar == Some(key) {
+                    scroll.horizontal_bar = None;
+                }
+            }
+            Children::List(list) => list.remove(key),
+            Children::None => (),
+        }
+    }
+}
+
 /// Describes allocated space for a child `Element` inside rows/columns
 #[derive(Clone, Debug)]
 pub struct Section {
@@ -0,0 +1,305 @@
+//! Virtual lists
+//!
+//! `Children::List` stands in for thousands of rows of the same height but
+//! only keeps `Element`s for the rows that can be seen. Put it inside a
+//! `Children::Scroll`, rows that scroll out of view are handed to the row
+//! builder again to show other rows.
+
+use std::collections::HashSet;
+
+use crate::{
+    rotate_point,
+    styles::{Container, Rectangle, Values, ViewPort},
+    Children, Element, ElementKey, ElementTransform, Gui, Point,
+};
+
+/// Fills the `Element` of a row with the row at the index
+///
+/// The same element is passed again whenever it is reused for another row,
+/// so keep children it already has instead of adding new ones. Called during
+/// `update`, don't change children of the list itself.
+pub type RowBuilder<Msg> = Box<dyn FnMut(&mut Gui<Msg>, usize, ElementKey)>;
+
+/// Rows of the same height that are only created when visible
+///
+/// ```ignore
+/// let list = gui.add_element(Element::new().with_children(Children::List(
+///     VirtualList::new(scores.len(), Values::Value(Value::Pixel(32.0))),
+/// )));
+/// gui.set_row_builder(list, move |gui, index, row| {
+///     if let Some(row) = gui.get_element_mut(row) {
+///         row.text_string(format!("{}. {}", index + 1, scores[index]));
+///     }
+/// });
+/// let leaderboard = Element::new().with_children(Children::Scroll(Scroll::new(list)));
+/// ```
+#[derive(Clone, Debug)]
+pub struct VirtualList {
+    /// Number of rows
+    pub count: usize,
+    /// Height of every row
+    pub row_height: Values,
+    /// Space between rows
+    pub spacing: Option<Values>,
+    /// Rows kept above and below the visible ones
+    pub overscan: usize,
+    /// Instantiated rows and their indices
+    rows: Vec<(usize, ElementKey)>,
+    /// Every row has to be built again
+    dirty: bool,
+}
+
+impl VirtualList {
+    pub fn new(count: usize, row_height: Values) -> Self {
+        Self {
+            count,
+            row_height,
+            spacing: None,
+            overscan: 2,
+            rows: Vec::new(),
+            dirty: false,
+        }
+    }
+
+    pub fn with_spacing(mut self, spacing: Values) -> Self {
+        self.spacing = Some(spacing);
+        self
+    }
+
+    pub fn with_overscan(mut self, overscan: usize) -> Self {
+        self.overscan = overscan;
+        self
+    }
+
+    /// Returns indices and elements of the instantiated rows
+    pub fn rows(&self) -> impl Iterator<Item = (usize, ElementKey)> + '_ {
+        self.rows.iter().copied()
+    }
+
+    /// Returns the element showing the row, if it is instantiated
+    pub fn row(&self, index: usize) -> Option<ElementKey> {
+        self.rows
+            .iter()
+            .find(|(i, _)| *i == index)
+            .map(|(_, key)| *key)
+    }
+
+    pub(crate) fn keys(&self) -> Vec<ElementKey> {
+        self.rows.iter().map(|(_, key)| *key).collect()
+    }
+
+    pub(crate) fn remove(&mut self, key: ElementKey) {
+        self.rows.retain(|(_, row)| *row != key);
+    }
+
+    /// Returns height of a row and of the space between rows
+    pub(crate) fn stride(&self, container: &Container, view_port: &ViewPort) -> (f32, f32) {
+        let height = self.row_height.calc(container, view_port).max(0.0);
+        let spacing = self
+            .spacing
+            .as_ref()
+            .map(|spacing| spacing.calc(container, view_port).max(0.0))
+            .unwrap_or(0.0);
+        (height, spacing)
+    }
+
+    /// Returns height of all rows together
+    pub(crate) fn height(&self, container: &Container, view_port: &ViewPort) -> f32 {
+        let (height, spacing) = self.stride(container, view_port);
+        height * self.count as f32 + spacing * self.count.saturating_sub(1) as f32
+    }
+}
+
+impl<Msg> Gui<Msg>
+where
+    Msg: Clone,
+{
+    /// Sets the callback that fills rows of a `Children::List` element
+    pub fn set_row_builder(
+        &mut self,
+        key: ElementKey,
+        builder: impl FnMut(&mut Gui<Msg>, usize, ElementKey) + 'static,
+    ) {
+        self.row_builders.insert(key, Box::new(builder));
+        self.refresh_rows(key);
+    }
+
+    /// Changes the number of rows of a `Children::List` element
+    ///
+    /// Rows are built again on the next `update`, the scroll position is kept
+    /// as long as there are enough rows.
+    pub fn set_row_count(&mut self, key: ElementKey, count: usize) {
+        if let Some(Children::List(list)) = self
+            .elements
+            .get_mut(key)
+            .map(|element| &mut element.children)
+        {
+            list.count = count;
+            list.dirty = true;
+        }
+    }
+
+    /// Builds every visible row again on the next `update`
+    ///
+    /// Use when the data behind the rows changed
+    pub fn refresh_rows(&mut self, key: ElementKey) {
+        if let Some(Children::List(list)) = self
+            .elements
+            .get_mut(key)
+            .map(|element| &mut element.children)
+        {
+            list.dirty = true;
+        }
+    }
+
+    /// Lays out children of a `Children::List` element inside `transform`
+    ///
+    /// Rows are created, reused or removed so that only the visible ones exist
+    pub(crate) fn list_transform(
+        &mut self,
+        key: ElementKey,
+        mut list: VirtualList,
+        transform: &ElementTransform,
+    ) {
+        let view_port = self.view_port();
+        let container: Container = transform.clone().into();
+        let (height, spacing) = list.stride(&container, &view_port);
+        let stride = height + spacing;
+        let top = transform.position.y - transform.scale.y / 2.0;
+
+        // part of the list inside of every clipping ancestor, in list coordinates
+        let visible = self.visible_bounds(key);
+        let inverse = transform.matrix.inverse().unwrap_or_default();
+        let corners = [
+            visible.position,
+            Point::new(visible.position.x + visible.size.x, visible.position.y),
+            Point::new(visible.position.x, visible.position.y + visible.size.y),
+            Point::new(
+                visible.position.x + visible.size.x,
+                visible.position.y + visible.size.y,
+            ),
+        ]
+        .map(|corner| {
+            rotate_point(
+                inverse.apply(corner),
+                transform.position,
+                -transform.rotation,
+            )
+            .y - top
+        });
+        let start = corners.iter().copied().fold(f32::MAX, f32::min).max(0.0);
+        let end = corners
+            .iter()
+            .copied()
+            .fold(f32::MIN, f32::max)
+            .min(transform.scale.y);
+
+        let mut needed = match stride > 0.0 && end > start {
+            true => {
+                let first = (start / stride).floor() as usize;
+                let last = (end / stride).ceil() as usize;
+                (first.saturating_sub(list.overscan)..(last + list.overscan).min(list.count))
+                    .collect::<HashSet<_>>()
+            }
+            false => HashSet::new(),
+        };
+        // the focused row is kept so that focus doesn't jump to another row
+        if let Some(selected) = self.select.selected {
+            let focused = list.rows.iter().find(|(_, row)| {
+                *row == selected || self.ancestors(selected).any(|key| key == *row)
+            });
+            if let Some((index, _)) = focused {
+                if *index < list.count {
+                    needed.insert(*index);
+                }
+            }
+        }
+
+        // rows keep their element when they stay visible
+        let mut kept = Vec::new();
+        let mut spare = Vec::new();
+        for (index, row) in list.rows.drain(..) {
+            match needed.remove(&index) {
+                true => kept.push((index, row)),
+                false => spare.push(row),
+            }
+        }
+        if std::mem::take(&mut list.dirty) {
+            for (index, row) in &kept {
+                self.build_row(key, *index, *row);
+            }
+        }
+        let mut missing = needed.into_iter().collect::<Vec<_>>();
+        missing.sort_unstable();
+        let changed = !missing.is_empty() || !spare.is_empty();
+        for index in missing {
+            let row = match spare.pop() {
+                Some(row) => row,
+                None => {
+                    let row = self.add_element(Element::new());
+                    self.adopt(key, &[row]);
+                    row
+                }
+            };
+            self.build_row(key, index, row);
+            kept.push((index, row));
+        }
+        for row in spare {
+            self.remove_subtree(row);
+        }
+        kept.sort_unstable_by_key(|(index, _)| *index);
+        list.rows = kept;
+        if changed {
+            self.rows_changed = true;
+        }
+
+        let rows = list.rows.clone();
+        if let Some(element) = self.elements.get_mut(key) {
+            element.children = Children::List(list);
+        }
+        for (index, row) in rows {
+            let center = Point::new(
+                transform.position.x,
+                top + index as f32 * stride + height / 2.0,
+            );
+            let position = if transform.rotation == 0.0 {
+                center
+            } else {
+                rotate_point(center, transform.position, transform.rotation)
+            };
+            self.element_transform(
+                row,
+                &ElementTransform {
+                    position,
+                    scale: Point::new(transform.scale.x, height),
+                    rotation: transform.rotation,
+                    matrix: transform.matrix,
+                },
+            );
+        }
+    }
+
+    /// Runs the row builder of the list for the row and measures it
+    fn build_row(&mut self, list: ElementKey, index: usize, row: ElementKey) {
+        if let Some(mut builder) = self.row_builders.remove(&list) {
+            builder(self, index, row);
+            self.row_builders.insert(list, builder);
+        }
+        self.measure(row);
+    }
+
+    /// Returns the part of the screen the element isn't clipped out of
+    fn visible_bounds(&self, key: ElementKey) -> Rectangle {
+        let screen = self.letterbox().unwrap_or(Rectangle {
+            position: Point::new(0.0, 0.0),
+            size: Point::new(self.size.0 as f32, self.size.1 as f32),
+        });
+        // clipping ancestors are already laid out
+        self.clip_chain(key)
+            .into_iter()
+            .filter_map(|clip| self.get_element(clip))
+            .fold(screen, |visible, element| {
+                crate::display_list::intersect(&visible, &element.transform.rect().bounds)
+            })
+    }
+}
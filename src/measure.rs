@@ -0,0 +1,296 @@
+//! Intrinsic sizes of `Element`s
+//!
+//! Before layout every element is measured bottom-up. The results back
+//! `Value::Text`, `Value::Image` and `Value::Content`, so an element can hug
+//! its label, background texture or children.
+
+use cosmic_text::{Attrs, Buffer, FontSystem, Metrics, Shaping};
+
+use crate::{
+    layout::{resolve_tracks, FlexDirection, Track},
+    styles::{Container, Rectangle, Values, ViewPort},
+    Children, ElementKey, Gui, Point,
+};
+
+/// Sizes of the things inside an `Element`
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct Intrinsic {
+    /// Shaped text and the font size it was shaped with
+    pub text: Option<(f32, Point)>,
+    pub image: Option<Point>,
+    pub children: Option<Point>,
+    /// Size the element would take if nothing constrained it
+    pub preferred: Point,
+}
+
+impl Intrinsic {
+    /// Smallest size that fits text, image and children
+    pub fn content(&self) -> Option<Point> {
+        [self.text.map(|(_, size)| size), self.image, self.children]
+            .into_iter()
+            .flatten()
+            .reduce(|a, b| Point::new(a.x.max(b.x), a.y.max(b.y)))
+    }
+
+    /// Makes the sizes available to `Values` resolved against `container`
+    pub fn apply(&self, container: &mut Container) {
+        let rectangle = |size| Rectangle {
+            position: Point::new(0.0, 0.0),
+            size,
+        };
+        container.image = self.image.map(rectangle);
+        container.text = self.text.map(|(_, size)| rectangle(size));
+        container.content = self.content().map(rectangle);
+    }
+}
+
+impl<Msg> Gui<Msg>
+where
+    Msg: Clone,
+{
+    /// Measures the element and everything below it
+    pub(crate) fn measure(&mut self, key: ElementKey) {
+        let mut font_system = match self.font_system.take() {
+            Some(font_system) => font_system,
+            None => return,
+        };
+        self.measure_element(key, &mut font_system);
+        self.font_system = Some(font_system);
+    }
+
+    /// Measures the element and returns the size it would take if nothing constrained it
+    ///
+    /// Values relative to the container are resolved against the content,
+    /// so an element that fills its container wraps its content instead.
+    fn measure_element(&mut self, key: ElementKey, font_system: &mut FontSystem) -> Point {
+        let children = match self.elements.get(key) {
+            Some(element) => element.children.clone(),
+            None => return Point::new(0.0, 0.0),
+        };
+        let view_port = self.view_port();
+        let empty = Container::from(crate::ElementTransform::zeroed());
+        let calc = |value: Option<&Values>| {
+            value
+                .map(|value| value.calc(&empty, &view_port).max(0.0))
+                .unwrap_or(0.0)
+        };
+        let rows = matches!(children, Children::Rows { .. });
+        let children = match children {
+            Children::Element(child) => Some(self.measure_element(child, font_system)),
+            Children::Layers(layers)
+            | Children::Constraints(crate::constraints::ConstraintLayout {
+                children: layers,
+                ..
+            }) => layers
+                .into_iter()
+                .map(|child| self.measure_element(child, font_system))
+                .reduce(|a, b| Point::new(a.x.max(b.x), a.y.max(b.y))),
+            Children::Rows {
+                children,
+                spacing,
+                padding,
+                ..
+            }
+            | Children::Columns {
+                children,
+                spacing,
+                padding,
+                ..
+            } if !children.is_empty() => {
+                let sizes = children
+                    .iter()
+                    .map(|section| {
+                        let size = self.measure_element(section.element, font_system);
+                        match (&section.size, rows) {
+                            (Some(_), true) => Point::new(size.x, calc(section.size.as_ref())),
+                            (Some(_), false) => Point::new(calc(section.size.as_ref()), size.y),
+                            (None, _) => size,
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                let around = calc(spacing.as_ref()) * (sizes.len() - 1) as f32
+                    + calc(padding.as_ref()) * 2.0;
+                Some(stack(&sizes, rows, around))
+            }
+            Children::Flex(flex) if !flex.children.is_empty() => {
+                let rows = matches!(
+                    flex.direction,
+                    FlexDirection::Column | FlexDirection::ColumnReverse
+                );
+                let sizes = flex
+                    .children
+                    .iter()
+                    .map(|item| {
+                        let size = self.measure_element(item.element, font_system);
+                        match (&item.basis, rows) {
+                            (Some(_), true) => Point::new(size.x, calc(item.basis.as_ref())),
+                            (Some(_), false) => Point::new(calc(item.basis.as_ref()), size.y),
+                            (None, _) => size,
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                let around = calc(flex.gap.as_ref()) * (sizes.len() - 1) as f32;
+                Some(stack(&sizes, rows, around))
+            }
+            Children::Grid(grid) if !grid.children.is_empty() => {
+                let items = grid
+                    .children
+                    .iter()
+                    .map(|item| {
+                        (
+                            item.clone(),
+                            self.measure_element(item.element, font_system),
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                let columns = tracks(
+                    &grid.columns,
+                    items
+                        .iter()
+                        .map(|(item, size)| (item.column, item.column_span, size.x)),
+                    calc(grid.column_gap.as_ref()),
+                    &calc,
+                );
+                let rows = tracks(
+                    &grid.rows,
+                    items
+                        .iter()
+                        .map(|(item, size)| (item.row, item.row_span, size.y)),
+                    calc(grid.row_gap.as_ref()),
+                    &calc,
+                );
+                Some(Point::new(columns, rows))
+            }
+            Children::Scroll(scroll) => {
+                for bar in [scroll.vertical_bar, scroll.horizontal_bar]
+                    .into_iter()
+                    .flatten()
+                {
+                    self.measure_element(bar, font_system);
+                }
+                Some(self.measure_element(scroll.content, font_system))
+            }
+            Children::List(list) => {
+                let rows = list
+                    .rows()
+                    .map(|(_, row)| self.measure_element(row, font_system))
+                    .fold(0.0, |width, size| size.x.max(width));
+                Some(Point::new(rows, list.height(&empty, &view_port)))
+            }
+            _ => None,
+        };
+
+        let elements = self.referenced(key);
+        let element = match self.elements.get_mut(key) {
+            Some(element) => element,
+            None => return Point::new(0.0, 0.0),
+        };
+        let padding = element.styles.padding.get().calc(&empty, &view_port);
+        element.intrinsic.children = children.map(|size| {
+            Point::new(
+                size.x + padding.horizontal().max(0.0),
+                size.y + padding.vertical().max(0.0),
+            )
+        });
+        element.intrinsic.image = element.styles.bg_texture.get().as_ref().map(|texture| {
+            let (width, height) = texture.dimensions();
+            Point::new(width as f32, height as f32)
+        });
+        element.measure_text(font_system, &view_port);
+
+        let mut container = Container::from(crate::ElementTransform::zeroed());
+        container.size = element.intrinsic.content().unwrap_or_default();
+        // referenced elements are where the last layout put them
+        container.elements = elements;
+        element.intrinsic.apply(&mut container);
+        let size = Point::new(
+            element.styles.width.get().calc(&container, &view_port),
+            element.styles.height.get().calc(&container, &view_port),
+        );
+        element.intrinsic.preferred = element.styles.clamp_size(size, &container, &view_port);
+        element.intrinsic.preferred
+    }
+}
+
+impl<Msg> crate::Element<Msg>
+where
+    Msg: Clone,
+{
+    /// Shapes the text unless it was already shaped with the same font size
+    fn measure_text(&mut self, font_system: &mut FontSystem, view_port: &ViewPort) {
+        let text = match self.text() {
+            Some(text) => text,
+            None => {
+                self.intrinsic.text = None;
+                return;
+            }
+        };
+        // the font size is resolved against the element itself, use the last
+        // layout if there was one
+        let container = match self.transform.scale.x > 0.0 && self.transform.scale.y > 0.0 {
+            true => Container::from(self.transform.clone()),
+            false => Container::from(crate::ElementTransform {
+                position: Point::new(view_port.0 / 2.0, view_port.1 / 2.0),
+                scale: Point::new(view_port.0, view_port.1),
+                rotation: 0.0,
+                matrix: crate::transform::Matrix::IDENTITY,
+            }),
+        };
+        let font_size = self.styles.text_size.get().calc(&container, view_port);
+        if let Some((measured, _)) = self.intrinsic.text {
+            if measured == font_size {
+                return;
+            }
+        }
+        let size = measure_text(text, font_size, font_system);
+        self.intrinsic.text = Some((font_size, size));
+    }
+}
+
+/// Returns size of the text when it is not wrapped
+fn measure_text(text: &str, font_size: f32, font_system: &mut FontSystem) -> Point {
+    if font_size <= 0.0 {
+        return Point::new(0.0, 0.0);
+    }
+    let mut buffer = Buffer::new(font_system, Metrics::new(font_size, font_size + 3.0));
+    let mut buffer = buffer.borrow_with(font_system);
+    buffer.set_size(None, None);
+    buffer.set_text(text, Attrs::new(), Shaping::Advanced);
+    buffer.shape_until_scroll(true);
+    let mut size = Point::new(0.0, 0.0);
+    for run in buffer.layout_runs() {
+        size.x = size.x.max(run.line_w);
+        size.y = size.y.max(run.line_top + run.line_height);
+    }
+    // the rasterizer wraps text that doesn't fit, keep it on one line
+    Point::new(size.x.ceil(), size.y.ceil())
+}
+
+/// Puts sizes one after another, vertically if `rows` is set
+fn stack(sizes: &[Point], rows: bool, around: f32) -> Point {
+    let main = sizes
+        .iter()
+        .map(|size| if rows { size.y } else { size.x })
+        .sum::<f32>();
+    let cross = sizes
+        .iter()
+        .map(|size| if rows { size.x } else { size.y })
+        .fold(0.0, f32::max);
+    match rows {
+        true => Point::new(cross, main + around),
+        false => Point::new(main + around, cross),
+    }
+}
+
+/// Returns total size of grid tracks that fit the children
+///
+/// Fractional tracks are measured like `Track::Auto`
+fn tracks(
+    tracks: &[Track],
+    items: impl Iterator<Item = (usize, usize, f32)> + Clone,
+    gap: f32,
+    calc: &impl Fn(Option<&Values>) -> f32,
+) -> f32 {
+    let sizes = resolve_tracks(tracks, items, None, gap, &|value| calc(Some(value)));
+    sizes.iter().sum::<f32>() + gap * sizes.len().saturating_sub(1) as f32
+}
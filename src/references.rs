@@ -0,0 +1,132 @@
+//! Elements that depend on other elements
+//!
+//! `Value::Element` and `Parent::Element` read where another element ended up
+//! during layout. After the tree is laid out, elements with such references
+//! are laid out again, each one after the elements it references. Elements
+//! that end up depending on themselves are reported and keep the first layout.
+
+use std::collections::HashSet;
+
+use crate::{styles::Container, Children, ElementKey, Gui};
+
+impl<Msg> Gui<Msg>
+where
+    Msg: Clone,
+{
+    /// Returns elements whose references lead back to themselves
+    ///
+    /// Updated on every `update`. They resolve references against the last
+    /// layout of the referenced elements, which may be a frame old.
+    pub fn layout_cycles(&self) -> &[ElementKey] {
+        &self.layout_cycles
+    }
+
+    /// Returns where the elements referenced by the element's styles are
+    pub(crate) fn referenced(&self, key: ElementKey) -> Vec<(ElementKey, Container)> {
+        let element = match self.get_element(key) {
+            Some(element) => element,
+            None => return Vec::new(),
+        };
+        element
+            .styles
+            .references()
+            .into_iter()
+            .filter_map(|reference| {
+                let transform = self.get_element(reference)?.transform.clone();
+                Some((reference, transform.into()))
+            })
+            .collect()
+    }
+
+    /// Lays out elements with references again once the referenced elements are in place
+    pub(crate) fn resolve_references(&mut self) {
+        let mut cycles = Vec::new();
+        // elements with references and the element laid out again to move them
+        let mut dependents = Vec::new();
+        let mut roots: Vec<(ElementKey, Vec<ElementKey>)> = Vec::new();
+        for key in self.ordered.clone() {
+            let references = match self.get_element(key) {
+                Some(element) => element.styles.references(),
+                None => continue,
+            };
+            if references.is_empty() {
+                continue;
+            }
+            // an element is placed before its children are
+            if references
+                .iter()
+                .any(|reference| *reference == key || self.ancestors(*reference).any(|a| a == key))
+            {
+                cycles.push(key);
+                continue;
+            }
+            let root = self.layout_root(key);
+            dependents.push((key, root));
+            match roots.iter_mut().find(|(other, _)| *other == root) {
+                Some((_, reads)) => reads.extend(references),
+                None => roots.push((root, references)),
+            }
+        }
+
+        // roots that have to be laid out before each root
+        let before = roots
+            .iter()
+            .map(|(root, reads)| {
+                roots
+                    .iter()
+                    .map(|(other, _)| *other)
+                    .filter(|other| other != root)
+                    .filter(|other| {
+                        self.ancestors(*root).any(|a| a == *other)
+                            || reads.iter().any(|read| {
+                                read == other || self.ancestors(*read).any(|a| a == *other)
+                            })
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let mut done = HashSet::new();
+        while let Some(index) = (0..roots.len()).find(|index| {
+            !done.contains(&roots[*index].0)
+                && before[*index].iter().all(|other| done.contains(other))
+        }) {
+            let root = roots[index].0;
+            done.insert(root);
+            let slot = self
+                .get_element(root)
+                .and_then(|element| element.slot.clone());
+            if let Some((transform, size)) = slot {
+                self.sized_element_transform(root, &transform, size);
+            }
+        }
+        cycles.extend(
+            dependents
+                .into_iter()
+                .filter(|(_, root)| !done.contains(root))
+                .map(|(key, _)| key),
+        );
+
+        self.layout_cycles = cycles;
+    }
+
+    /// Returns the element to lay out again to move the element
+    ///
+    /// Rows, columns, flex, grid and constraints size children from their
+    /// styles, the whole layout runs again.
+    fn layout_root(&self, key: ElementKey) -> ElementKey {
+        let parent = match self.parent_of(key) {
+            Some(parent) => parent,
+            None => return key,
+        };
+        match self.get_element(parent).map(|element| &element.children) {
+            Some(
+                Children::Rows { .. }
+                | Children::Columns { .. }
+                | Children::Flex(_)
+                | Children::Grid(_)
+                | Children::Constraints(_),
+            ) => parent,
+            _ => key,
+        }
+    }
+}
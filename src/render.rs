@@ -1,5 +1,6 @@
 use std::sync::Arc;
 
+use crate::display_list::{DrawCommand, Fill};
 use crate::styles::Color;
 use crate::Point;
 use wgpu::{
@@ -8,6 +9,7 @@ use wgpu::{
 };
 
 use crate::texture::Texture;
+use crate::transform::Matrix;
 
 pub struct GpuBound {
     pub dimensions_buffer: wgpu::Buffer,
@@ -24,6 +26,10 @@ pub struct Pipelines {
     pub radial_gradient_pipeline: wgpu::RenderPipeline,
     pub linear_gradient_pipeline: wgpu::RenderPipeline,
     pub instancing_pipeline: wgpu::RenderPipeline,
+    /// Adds the shape of a clipping element to the stencil
+    pub clip_push_pipeline: Option<wgpu::RenderPipeline>,
+    /// Removes the shape of a clipping element from the stencil
+    pub clip_pop_pipeline: Option<wgpu::RenderPipeline>,
 }
 
 impl GpuBound {
@@ -42,7 +48,37 @@ impl GpuBound {
             }],
         };
 
-    pub fn new(queue: &wgpu::Queue, device: &wgpu::Device, size: (u32, u32)) -> Self {
+    pub const STENCIL_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Stencil8;
+
+    /// Draws where the stencil equals the reference and applies `pass_op` there
+    fn depth_stencil(pass_op: wgpu::StencilOperation) -> wgpu::DepthStencilState {
+        let face = wgpu::StencilFaceState {
+            compare: wgpu::CompareFunction::Equal,
+            fail_op: wgpu::StencilOperation::Keep,
+            depth_fail_op: wgpu::StencilOperation::Keep,
+            pass_op,
+        };
+        wgpu::DepthStencilState {
+            format: Self::STENCIL_FORMAT,
+            depth_write_enabled: false,
+            depth_compare: wgpu::CompareFunction::Always,
+            stencil: wgpu::StencilState {
+                front: face,
+                back: face,
+                read_mask: !0,
+                write_mask: !0,
+            },
+            bias: wgpu::DepthBiasState::default(),
+        }
+    }
+
+    /// Creates the pipelines, with `stencil` they expect a `STENCIL_FORMAT` attachment
+    pub fn new(
+        queue: &wgpu::Queue,
+        device: &wgpu::Device,
+        size: (u32, u32),
+        stencil: bool,
+    ) -> Self {
         let dimensions_bind_group_layout =
             device.create_bind_group_layout(&Self::DIMENSIONS_LAYOUT);
 
@@ -72,6 +108,9 @@ impl GpuBound {
             bytemuck::cast_slice(&[size.0 as f32, size.1 as f32]),
         );
 
+        let depth_stencil =
+            stencil.then(|| Self::depth_stencil(wgpu::StencilOperation::Keep));
+
         let elements_bind_group_layout = device.create_bind_group_layout(&RenderElement::LAYOUT);
         let color_bind_group_layout = device.create_bind_group_layout(&Color::BIND_GROUP_LAYOUT);
         let texture_bind_group_layout =
@@ -122,7 +161,7 @@ impl GpuBound {
                 conservative: false,
                 ..Default::default()
             },
-            depth_stencil: None,
+            depth_stencil: depth_stencil.clone(),
             multisample: wgpu::MultisampleState {
                 count: 1,
                 mask: !0,
@@ -173,7 +212,7 @@ impl GpuBound {
                 conservative: false,
                 ..Default::default()
             },
-            depth_stencil: None,
+            depth_stencil: depth_stencil.clone(),
             multisample: wgpu::MultisampleState {
                 count: 1,
                 mask: !0,
@@ -226,7 +265,7 @@ impl GpuBound {
                     conservative: false,
                     ..Default::default()
                 },
-                depth_stencil: None,
+                depth_stencil: depth_stencil.clone(),
                 multisample: wgpu::MultisampleState {
                     count: 1,
                     mask: !0,
@@ -279,7 +318,7 @@ impl GpuBound {
                     conservative: false,
                     ..Default::default()
                 },
-                depth_stencil: None,
+                depth_stencil: depth_stencil.clone(),
                 multisample: wgpu::MultisampleState {
                     count: 1,
                     mask: !0,
@@ -337,7 +376,7 @@ impl GpuBound {
                     conservative: false,
                     ..Default::default()
                 },
-                depth_stencil: None,
+                depth_stencil: depth_stencil.clone(),
                 multisample: wgpu::MultisampleState {
                     count: 1,
                     mask: !0,
@@ -347,6 +386,62 @@ impl GpuBound {
                 cache: None,
             });
 
+        let clip_pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("Clip Pipeline Layout"),
+                bind_group_layouts: &[
+                    &dimensions_bind_group_layout,
+                    &elements_bind_group_layout,
+                ],
+                push_constant_ranges: &[],
+            });
+
+        let clip_shaders = device.create_shader_module(include_wgsl!("shaders/clip.wgsl"));
+
+        let clip_pipeline = |pass_op| {
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some("Clip Pipeline"),
+                layout: Some(&clip_pipeline_layout),
+                vertex: wgpu::VertexState {
+                    module: &clip_shaders,
+                    entry_point: "vs_main",
+                    buffers: &[],
+                    compilation_options: Default::default(),
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: &clip_shaders,
+                    entry_point: "fs_main",
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format: wgpu::TextureFormat::Bgra8UnormSrgb,
+                        blend: None,
+                        write_mask: wgpu::ColorWrites::empty(),
+                    })],
+                    compilation_options: Default::default(),
+                }),
+                primitive: wgpu::PrimitiveState {
+                    topology: wgpu::PrimitiveTopology::TriangleList,
+                    strip_index_format: None,
+                    front_face: wgpu::FrontFace::Ccw,
+                    cull_mode: None,
+                    polygon_mode: wgpu::PolygonMode::Fill,
+                    conservative: false,
+                    ..Default::default()
+                },
+                depth_stencil: Some(Self::depth_stencil(pass_op)),
+                multisample: wgpu::MultisampleState {
+                    count: 1,
+                    mask: !0,
+                    alpha_to_coverage_enabled: false,
+                },
+                multiview: None,
+                cache: None,
+            })
+        };
+        let clip_push_pipeline =
+            stencil.then(|| clip_pipeline(wgpu::StencilOperation::IncrementClamp));
+        let clip_pop_pipeline =
+            stencil.then(|| clip_pipeline(wgpu::StencilOperation::DecrementClamp));
+
         Self {
             dimensions_buffer,
             dimensions_bind_group,
@@ -358,6 +453,8 @@ impl GpuBound {
                 radial_gradient_pipeline,
                 linear_gradient_pipeline,
                 instancing_pipeline,
+                clip_push_pipeline,
+                clip_pop_pipeline,
             },
         }
     }
@@ -783,6 +880,7 @@ pub struct RenderElement {
     pub rotation_buffer: wgpu::Buffer,
     pub alpha_buffer: wgpu::Buffer,
     pub edges_buffer: wgpu::Buffer,
+    pub transform_buffer: wgpu::Buffer,
     pub bind_group: wgpu::BindGroup,
     pub color: Option<RenderColor>,
     pub texture: Option<Arc<Texture>>,
@@ -859,6 +957,8 @@ pub struct RenderElementData {
     pub text_size: f32,
     pub lin_grad: Option<LinearGradientData>,
     pub rad_grad: Option<RadialGradientData>,
+    /// Applied to the rotated quad
+    pub transform: Matrix,
 }
 
 #[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
@@ -951,6 +1051,7 @@ impl RenderElementData {
             text_size,
             lin_grad,
             rad_grad,
+            transform: Matrix::IDENTITY,
         }
     }
 
@@ -969,20 +1070,25 @@ impl RenderElementData {
         text_size: 20.0,
         lin_grad: None,
         rad_grad: None,
+        transform: Matrix::IDENTITY,
     };
 
     pub(crate) fn update_transform(&mut self, transform: &crate::ElementTransform) {
         self.center = [transform.position.x, transform.position.y];
         self.size = [transform.scale.x, transform.scale.y];
         self.rotation = transform.rotation;
+        self.transform = transform.matrix;
     }
 
     pub fn point_collision(&self, point: Point) -> bool {
-        let rotated_point = Point::new(
-            (point.x - self.center[0]) * self.rotation.cos()
-                - (point.y - self.center[1]) * self.rotation.sin(),
-            (point.x - self.center[0]) * self.rotation.sin()
-                + (point.y - self.center[1]) * self.rotation.cos(),
+        let point = match self.transform.inverse() {
+            Some(inverse) => inverse.apply(point),
+            None => return false,
+        };
+        let rotated_point = crate::rotate_point(
+            Point::new(point.x - self.center[0], point.y - self.center[1]),
+            Point::new(0.0, 0.0),
+            -self.rotation,
         );
 
         let (width, height) = (self.size[0] / 2.0, self.size[1] / 2.0);
@@ -1048,6 +1154,16 @@ impl RenderElement {
                 },
                 count: None,
             },
+            wgpu::BindGroupLayoutEntry {
+                binding: 5,
+                visibility: wgpu::ShaderStages::all(),
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            },
         ],
     };
 
@@ -1087,6 +1203,13 @@ impl RenderElement {
             mapped_at_creation: false,
         });
 
+        let transform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Transform Buffer"),
+            size: std::mem::size_of::<[[f32; 4]; 3]>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
         let bind_group_layout = device.create_bind_group_layout(&Self::LAYOUT);
 
         let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
@@ -1133,6 +1256,14 @@ impl RenderElement {
                         size: None,
                     }),
                 },
+                wgpu::BindGroupEntry {
+                    binding: 5,
+                    resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+                        buffer: &transform_buffer,
+                        offset: 0,
+                        size: None,
+                    }),
+                },
             ],
         });
 
@@ -1142,6 +1273,7 @@ impl RenderElement {
             rotation_buffer,
             alpha_buffer,
             edges_buffer,
+            transform_buffer,
             bind_group,
             color: None,
             texture: None,
@@ -1189,45 +1321,55 @@ impl RenderElement {
             0,
             bytemuck::cast_slice(&[data.alpha]),
         );
+        queue.write_buffer(
+            &self.transform_buffer,
+            0,
+            bytemuck::cast_slice(&data.transform.to_mat3()),
+        );
     }
 
     pub fn bind(&self) -> &wgpu::BindGroup {
         &self.bind_group
     }
 
-    pub fn render(&self, pipelines: &Pipelines, pass: &mut wgpu::RenderPass) {
-        if self.color.is_none()
-            && self.texture.is_none()
-            && self.radial_gradient.is_none()
-            && self.linear_gradient.is_none()
-        {
-            return;
-        } else {
+    /// Draws a command that belongs to this element
+    pub fn draw(&self, command: &DrawCommand, pipelines: &Pipelines, pass: &mut wgpu::RenderPass) {
+        let (pipeline, bind_group) = match command {
+            DrawCommand::Quad { fill, .. } => match fill {
+                Fill::Texture(_) => (
+                    &pipelines.texture_pipeline,
+                    self.texture.as_ref().and_then(|texture| texture.gpu()).map(|texture| &texture.bind_group),
+                ),
+                Fill::RadialGradient(_) => (
+                    &pipelines.radial_gradient_pipeline,
+                    self.radial_gradient.as_ref().map(|grad| grad.bind()),
+                ),
+                Fill::LinearGradient(_) => (
+                    &pipelines.linear_gradient_pipeline,
+                    self.linear_gradient.as_ref().map(|grad| grad.bind()),
+                ),
+                Fill::Color(_) => (
+                    &pipelines.color_pipeline,
+                    self.color.as_ref().map(|color| color.bind()),
+                ),
+            },
+            DrawCommand::Text { .. } => (
+                &pipelines.texture_pipeline,
+                self.text.as_ref().and_then(|texture| texture.gpu()).map(|texture| &texture.bind_group),
+            ),
+            DrawCommand::PushClip { .. } | DrawCommand::PopClip => return,
+        };
+        if let Some(bind_group) = bind_group {
             pass.set_bind_group(1, self.bind(), &[]);
+            Self::draw_command(pipeline, pass, bind_group);
         }
-        if let Some(texture) = &self.texture {
-            Self::draw_command(&pipelines.texture_pipeline, pass, &texture.bind_group);
-        }
-        if let Some(radial_gradient) = &self.radial_gradient {
-            Self::draw_command(
-                &pipelines.radial_gradient_pipeline,
-                pass,
-                &radial_gradient.bind_group,
-            );
-        }
-        if let Some(linear_gradient) = &self.linear_gradient {
-            Self::draw_command(
-                &pipelines.linear_gradient_pipeline,
-                pass,
-                &linear_gradient.bind_group,
-            );
-        }
-        if let Some(render_color) = &self.color {
-            Self::draw_command(&pipelines.color_pipeline, pass, render_color.bind());
-        }
-        if let Some(texture) = &self.text {
-            Self::draw_command(&pipelines.texture_pipeline, pass, &texture.bind_group);
-        }
+    }
+
+    /// Draws the shape of the element with a stencil pipeline of `Pipelines`
+    pub fn draw_clip(&self, pipeline: &wgpu::RenderPipeline, pass: &mut wgpu::RenderPass) {
+        pass.set_pipeline(pipeline);
+        pass.set_bind_group(1, self.bind(), &[]);
+        pass.draw(0..6, 0..1);
     }
 
     fn draw_command(
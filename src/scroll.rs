@@ -0,0 +1,433 @@
+//! Scroll containers
+//!
+//! `Children::Scroll` shows a window into content that is bigger than the
+//! element. The content is laid out at its measured size, moved by the scroll
+//! offset and clipped to the element. Scrollbar thumbs are ordinary elements,
+//! style them however you like and the `Gui` sizes, places and drags them.
+
+use crate::{
+    rotate_point,
+    styles::{Container, Rectangle},
+    transform::Matrix,
+    Children, ElementKey, ElementTransform, Gui, Point,
+};
+
+/// Shows part of an element that is bigger than its container
+///
+/// ```ignore
+/// let mut thumb = Element::new();
+/// thumb.styles.width.set(Values::Value(Value::Pixel(6.0)));
+/// thumb.styles.bg_color.set(Colors::GRAY);
+/// let thumb = gui.add_element(thumb);
+///
+/// let quest_log = Element::new().with_children(Children::Scroll(
+///     Scroll::new(quests).with_vertical_bar(thumb),
+/// ));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Scroll {
+    /// Scrolled `Element`
+    pub content: ElementKey,
+    /// Axes the content can be scrolled along
+    pub axis: ScrollAxis,
+    /// Thumb of the vertical scrollbar, its width is the width of the bar
+    pub vertical_bar: Option<ElementKey>,
+    /// Thumb of the horizontal scrollbar, its height is the height of the bar
+    pub horizontal_bar: Option<ElementKey>,
+    /// Logical pixels scrolled per unit of `WindowEvent::Scroll` delta
+    pub step: f32,
+    offset: Point,
+    content_size: Point,
+    view_size: Point,
+}
+
+impl Scroll {
+    pub fn new(content: ElementKey) -> Self {
+        Self {
+            content,
+            axis: ScrollAxis::Vertical,
+            vertical_bar: None,
+            horizontal_bar: None,
+            step: 40.0,
+            offset: Point::new(0.0, 0.0),
+            content_size: Point::new(0.0, 0.0),
+            view_size: Point::new(0.0, 0.0),
+        }
+    }
+
+    pub fn with_axis(mut self, axis: ScrollAxis) -> Self {
+        self.axis = axis;
+        self
+    }
+
+    pub fn with_vertical_bar(mut self, thumb: ElementKey) -> Self {
+        self.vertical_bar = Some(thumb);
+        self
+    }
+
+    pub fn with_horizontal_bar(mut self, thumb: ElementKey) -> Self {
+        self.horizontal_bar = Some(thumb);
+        self
+    }
+
+    pub fn with_step(mut self, step: f32) -> Self {
+        self.step = step;
+        self
+    }
+
+    /// Returns how far the content is scrolled
+    pub fn offset(&self) -> Point {
+        self.offset
+    }
+
+    /// Returns the largest offset, measured by the last layout
+    pub fn max_offset(&self) -> Point {
+        Point::new(
+            match self.axis.horizontal() {
+                true => (self.content_size.x - self.view_size.x).max(0.0),
+                false => 0.0,
+            },
+            match self.axis.vertical() {
+                true => (self.content_size.y - self.view_size.y).max(0.0),
+                false => 0.0,
+            },
+        )
+    }
+
+    fn clamp(&mut self) {
+        let max = self.max_offset();
+        self.offset = Point::new(
+            self.offset.x.min(max.x).max(0.0),
+            self.offset.y.min(max.y).max(0.0),
+        );
+    }
+
+    /// Returns offset and length of the thumb along the bar
+    fn thumb(&self, vertical: bool) -> (f32, f32) {
+        let (view, content, offset, max) = match vertical {
+            true => (
+                self.view_size.y,
+                self.content_size.y,
+                self.offset.y,
+                self.max_offset().y,
+            ),
+            false => (
+                self.view_size.x,
+                self.content_size.x,
+                self.offset.x,
+                self.max_offset().x,
+            ),
+        };
+        if max <= 0.0 {
+            return (0.0, 0.0);
+        }
+        let length = view * view / content;
+        (offset / max * (view - length), length)
+    }
+}
+
+/// Axes a `Scroll` container can be scrolled along
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ScrollAxis {
+    #[default]
+    Vertical,
+    Horizontal,
+    Both,
+}
+
+impl ScrollAxis {
+    fn vertical(&self) -> bool {
+        matches!(self, ScrollAxis::Vertical | ScrollAxis::Both)
+    }
+
+    fn horizontal(&self) -> bool {
+        matches!(self, ScrollAxis::Horizontal | ScrollAxis::Both)
+    }
+}
+
+/// Scrollbar thumb being dragged by the mouse
+pub(crate) struct ScrollDrag {
+    scroll: ElementKey,
+    vertical: bool,
+    /// Mouse position along the bar when the drag started
+    grab: f32,
+    /// Scroll offset when the drag started
+    offset: f32,
+}
+
+impl<Msg> Gui<Msg>
+where
+    Msg: Clone,
+{
+    /// Returns the scroll offset of a `Children::Scroll` element
+    pub fn scroll_offset(&self, key: ElementKey) -> Option<Point> {
+        match &self.get_element(key)?.children {
+            Children::Scroll(scroll) => Some(scroll.offset),
+            _ => None,
+        }
+    }
+
+    /// Scrolls a `Children::Scroll` element
+    ///
+    /// The offset is clamped to the content size of the last layout
+    pub fn set_scroll_offset(&mut self, key: ElementKey, offset: Point) {
+        if let Some(Children::Scroll(scroll)) = self
+            .elements
+            .get_mut(key)
+            .map(|element| &mut element.children)
+        {
+            scroll.offset = offset;
+            scroll.clamp();
+        }
+    }
+
+    /// Scrolls every scroll container above the element so it becomes visible
+    ///
+    /// Useful to follow keyboard or gamepad focus. Uses positions from
+    /// the last layout, the change shows after the next `update`.
+    pub fn scroll_to(&mut self, key: ElementKey) {
+        let target = match self.get_element(key) {
+            Some(element) => layout_bounds(&element.transform),
+            None => return,
+        };
+        let ancestors = self.ancestors(key).collect::<Vec<_>>();
+        for ancestor in ancestors {
+            let element = match self.elements.get(ancestor) {
+                Some(element) => element,
+                None => continue,
+            };
+            let view = layout_bounds(&element.transform);
+            let mut scroll = match &element.children {
+                Children::Scroll(scroll) => scroll.clone(),
+                _ => continue,
+            };
+            // distance the target sticks out of the view, scrolled by the difference
+            let reveal = |start: f32, size: f32, view_start: f32, view_size: f32| {
+                if size > view_size || start < view_start {
+                    start - view_start
+                } else if start + size > view_start + view_size {
+                    start + size - view_start - view_size
+                } else {
+                    0.0
+                }
+            };
+            if scroll.axis.horizontal() {
+                scroll.offset.x += reveal(
+                    target.position.x,
+                    target.size.x,
+                    view.position.x,
+                    view.size.x,
+                );
+            }
+            if scroll.axis.vertical() {
+                scroll.offset.y += reveal(
+                    target.position.y,
+                    target.size.y,
+                    view.position.y,
+                    view.size.y,
+                );
+            }
+            scroll.clamp();
+            if let Some(element) = self.elements.get_mut(ancestor) {
+                element.children = Children::Scroll(scroll);
+            }
+        }
+    }
+
+    /// Lays out children of a `Children::Scroll` element inside `transform`
+    pub(crate) fn scroll_transform(
+        &mut self,
+        key: ElementKey,
+        mut scroll: Scroll,
+        transform: &ElementTransform,
+    ) {
+        let view_port = self.view_port();
+        let view = transform.scale;
+        let preferred = self
+            .get_element(scroll.content)
+            .map(|element| element.intrinsic.preferred)
+            .unwrap_or_default();
+        scroll.view_size = view;
+        scroll.content_size = Point::new(
+            match scroll.axis.horizontal() {
+                true => preferred.x.max(view.x),
+                false => view.x,
+            },
+            match scroll.axis.vertical() {
+                true => preferred.y.max(view.y),
+                false => view.y,
+            },
+        );
+        scroll.clamp();
+
+        let place = |offset: Point, size: Point| {
+            let point = Point::new(
+                transform.position.x - view.x / 2.0 + offset.x + size.x / 2.0,
+                transform.position.y - view.y / 2.0 + offset.y + size.y / 2.0,
+            );
+            let position = if transform.rotation == 0.0 {
+                point
+            } else {
+                rotate_point(point, transform.position, transform.rotation)
+            };
+            ElementTransform {
+                position,
+                scale: size,
+                rotation: transform.rotation,
+                matrix: transform.matrix,
+            }
+        };
+
+        let content = place(
+            Point::new(-scroll.offset.x, -scroll.offset.y),
+            scroll.content_size,
+        );
+        self.element_transform(scroll.content, &content);
+
+        let container: Container = transform.clone().into();
+        for (bar, vertical) in [(scroll.vertical_bar, true), (scroll.horizontal_bar, false)] {
+            let bar = match bar {
+                Some(bar) => bar,
+                None => continue,
+            };
+            let thickness = match self.get_element(bar) {
+                Some(element) if vertical => element.styles.width.get(),
+                Some(element) => element.styles.height.get(),
+                None => continue,
+            }
+            .calc(&container, &view_port);
+            let (start, length) = scroll.thumb(vertical);
+            let (offset, size) = match vertical {
+                true => (
+                    Point::new(view.x - thickness, start),
+                    Point::new(thickness, length),
+                ),
+                false => (
+                    Point::new(start, view.y - thickness),
+                    Point::new(length, thickness),
+                ),
+            };
+            let thumb = place(offset, size);
+            self.sized_element_transform(bar, &thumb, Some(size));
+        }
+
+        if let Some(element) = self.elements.get_mut(key) {
+            element.children = Children::Scroll(scroll);
+        }
+    }
+
+    /// Scrolls the innermost scroll container under the mouse that can move
+    pub(crate) fn scroll_wheel(&mut self, delta: Point) {
+        let hovered = self.ordered.iter().rev().find(|key| {
+            self.get_element(**key)
+                .is_some_and(|element| element.transform.point_collision(self.input.mouse))
+                && self.unclipped(**key, self.input.mouse)
+        });
+        let mut current = hovered.copied();
+        let scale_factor = self.scale_factor;
+        while let Some(key) = current {
+            current = self.parent_of(key);
+            let scroll = match self
+                .elements
+                .get_mut(key)
+                .map(|element| &mut element.children)
+            {
+                Some(Children::Scroll(scroll)) => scroll,
+                _ => continue,
+            };
+            let before = scroll.offset;
+            scroll.offset.x -= delta.x * scroll.step * scale_factor;
+            scroll.offset.y -= delta.y * scroll.step * scale_factor;
+            scroll.clamp();
+            if scroll.offset.x != before.x || scroll.offset.y != before.y {
+                return;
+            }
+        }
+    }
+
+    /// Starts dragging a scrollbar thumb if the mouse is over one
+    pub(crate) fn grab_scrollbar(&mut self) {
+        let mouse = self.input.mouse;
+        let thumb = self.ordered.iter().rev().find_map(|key| {
+            let element = self.get_element(*key)?;
+            if !element.transform.point_collision(mouse) || !self.unclipped(*key, mouse) {
+                return None;
+            }
+            let parent = self.parent_of(*key)?;
+            match &self.get_element(parent)?.children {
+                Children::Scroll(scroll) if scroll.vertical_bar == Some(*key) => {
+                    Some((parent, true))
+                }
+                Children::Scroll(scroll) if scroll.horizontal_bar == Some(*key) => {
+                    Some((parent, false))
+                }
+                _ => None,
+            }
+        });
+        let (key, vertical) = match thumb {
+            Some(thumb) => thumb,
+            None => return,
+        };
+        let element = match self.get_element(key) {
+            Some(element) => element,
+            None => return,
+        };
+        let (mouse, offset) = match &element.children {
+            Children::Scroll(scroll) => (element.place_point(mouse), scroll.offset),
+            _ => return,
+        };
+        self.input.drag = Some(ScrollDrag {
+            scroll: key,
+            vertical,
+            grab: if vertical { mouse.y } else { mouse.x },
+            offset: if vertical { offset.y } else { offset.x },
+        });
+    }
+
+    /// Moves the content of the dragged scrollbar
+    pub(crate) fn drag_scrollbar(&mut self) {
+        let drag = match &self.input.drag {
+            Some(drag) => drag,
+            None => return,
+        };
+        let element = match self.elements.get_mut(drag.scroll) {
+            Some(element) => element,
+            None => {
+                self.input.drag = None;
+                return;
+            }
+        };
+        let mouse = element.place_point(self.input.mouse);
+        let scroll = match &mut element.children {
+            Children::Scroll(scroll) => scroll,
+            _ => return,
+        };
+        let (_, length) = scroll.thumb(drag.vertical);
+        let (view, max) = match drag.vertical {
+            true => (scroll.view_size.y, scroll.max_offset().y),
+            false => (scroll.view_size.x, scroll.max_offset().x),
+        };
+        if view - length <= 0.0 {
+            return;
+        }
+        let moved = if drag.vertical { mouse.y } else { mouse.x } - drag.grab;
+        let offset = drag.offset + moved * max / (view - length);
+        match drag.vertical {
+            true => scroll.offset.y = offset,
+            false => scroll.offset.x = offset,
+        }
+        scroll.clamp();
+    }
+}
+
+/// Returns the box around the element before `transform` styles
+///
+/// Scroll offsets are in layout coordinates
+fn layout_bounds(transform: &ElementTransform) -> Rectangle {
+    ElementTransform {
+        matrix: Matrix::IDENTITY,
+        ..transform.clone()
+    }
+    .rect()
+    .bounds
+}
@@ -0,0 +1,207 @@
+//! Selector queries over `Element` labels
+//!
+//! A selector is a list of labels separated by combinators:
+//!
+//! - `a > b` matches `b` whose parent is `a`
+//! - `a b` matches `b` that has `a` anywhere above it
+//! - `*` matches any element
+//!
+//! Labels containing spaces or special characters can be quoted: `'main menu' > button`
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::{ElementKey, Gui};
+
+/// Parsed selector
+///
+/// Parse once with `Selector::parse` and reuse it with `Gui::query_selector`
+/// if you query the same thing every frame
+#[derive(Debug, Clone, PartialEq)]
+pub struct Selector {
+    /// Compounds from left to right, combinator describes relation to the previous compound
+    parts: Vec<(Combinator, Compound)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Combinator {
+    Child,
+    Descendant,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Compound {
+    Label(String),
+    Any,
+}
+
+/// Error returned when a selector can't be parsed
+#[derive(Debug, Clone, PartialEq)]
+pub enum SelectorError {
+    /// The selector contains no labels
+    Empty,
+    /// A combinator is missing a label on one of its sides
+    DanglingCombinator { position: usize },
+    /// A quoted label is missing its closing quote
+    UnterminatedQuote { position: usize },
+}
+
+impl fmt::Display for SelectorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SelectorError::Empty => write!(f, "selector is empty"),
+            SelectorError::DanglingCombinator { position } => {
+                write!(f, "expected a label around '>' at position {}", position)
+            }
+            SelectorError::UnterminatedQuote { position } => {
+                write!(f, "quote opened at position {} is never closed", position)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SelectorError {}
+
+impl Selector {
+    pub fn parse(selector: &str) -> Result<Self, SelectorError> {
+        let mut parts = Vec::new();
+        let mut combinator = None;
+        let mut chars = selector.char_indices().peekable();
+        while let Some(&(position, c)) = chars.peek() {
+            match c {
+                c if c.is_whitespace() => {
+                    chars.next();
+                    if !parts.is_empty() && combinator.is_none() {
+                        combinator = Some((Combinator::Descendant, position));
+                    }
+                }
+                '>' => {
+                    chars.next();
+                    if parts.is_empty() || matches!(combinator, Some((Combinator::Child, _))) {
+                        return Err(SelectorError::DanglingCombinator { position });
+                    }
+                    combinator = Some((Combinator::Child, position));
+                }
+                '\'' | '"' => {
+                    chars.next();
+                    let mut label = String::new();
+                    let mut closed = false;
+                    for (_, next) in chars.by_ref() {
+                        if next == c {
+                            closed = true;
+                            break;
+                        }
+                        label.push(next);
+                    }
+                    if !closed {
+                        return Err(SelectorError::UnterminatedQuote { position });
+                    }
+                    parts.push((Self::combinator(combinator.take()), Compound::Label(label)));
+                }
+                _ => {
+                    let mut label = String::new();
+                    while let Some(&(_, next)) = chars.peek() {
+                        if next.is_whitespace() || next == '>' || next == '\'' || next == '"' {
+                            break;
+                        }
+                        label.push(next);
+                        chars.next();
+                    }
+                    let compound = match label.as_str() {
+                        "*" => Compound::Any,
+                        _ => Compound::Label(label),
+                    };
+                    parts.push((Self::combinator(combinator.take()), compound));
+                }
+            }
+        }
+        match combinator {
+            Some((Combinator::Child, position)) => {
+                Err(SelectorError::DanglingCombinator { position })
+            }
+            _ if parts.is_empty() => Err(SelectorError::Empty),
+            _ => Ok(Self { parts }),
+        }
+    }
+
+    fn combinator(combinator: Option<(Combinator, usize)>) -> Combinator {
+        combinator
+            .map(|(combinator, _)| combinator)
+            .unwrap_or(Combinator::Descendant)
+    }
+}
+
+impl FromStr for Selector {
+    type Err = SelectorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl<Msg> Gui<Msg>
+where
+    Msg: Clone,
+{
+    /// Returns all elements matching the selector
+    ///
+    /// ```ignore
+    /// let slots = gui.query("inventory > row > slot")?;
+    /// ```
+    pub fn query(&self, selector: &str) -> Result<Vec<ElementKey>, SelectorError> {
+        Ok(self.query_selector(&Selector::parse(selector)?))
+    }
+
+    /// Returns the first element matching the selector
+    pub fn query_first(&self, selector: &str) -> Result<Option<ElementKey>, SelectorError> {
+        Ok(self.query_selector(&Selector::parse(selector)?).into_iter().next())
+    }
+
+    /// Returns all elements matching an already parsed selector
+    pub fn query_selector(&self, selector: &Selector) -> Vec<ElementKey> {
+        let last = selector.parts.len() - 1;
+        let candidates = match &selector.parts[last].1 {
+            Compound::Label(label) => self.find_all_by_label(label),
+            Compound::Any => self.elements.iter().map(|(key, _)| key).collect(),
+        };
+        candidates
+            .into_iter()
+            .filter(|key| self.matches_from(*key, &selector.parts[..last], selector.parts[last].0))
+            .collect()
+    }
+
+    /// Checks that ancestors of `key` satisfy the remaining compounds
+    fn matches_from(
+        &self,
+        key: ElementKey,
+        rest: &[(Combinator, Compound)],
+        combinator: Combinator,
+    ) -> bool {
+        let (last, rest) = match rest.split_last() {
+            Some(split) => split,
+            None => return true,
+        };
+        match combinator {
+            Combinator::Child => match self.parent_of(key) {
+                Some(parent) => {
+                    self.compound_matches(parent, &last.1)
+                        && self.matches_from(parent, rest, last.0)
+                }
+                None => false,
+            },
+            Combinator::Descendant => self.ancestors(key).any(|ancestor| {
+                self.compound_matches(ancestor, &last.1)
+                    && self.matches_from(ancestor, rest, last.0)
+            }),
+        }
+    }
+
+    fn compound_matches(&self, key: ElementKey, compound: &Compound) -> bool {
+        match compound {
+            Compound::Any => true,
+            Compound::Label(label) => self
+                .get_element(key)
+                .is_some_and(|element| element.label.as_deref() == Some(label.as_str())),
+        }
+    }
+}
@@ -0,0 +1,72 @@
+
+struct VertexInput {
+    @builtin(vertex_index) index: u32
+}
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) clip_position: vec2<f32>,
+}
+
+@group(0)@binding(0) var<uniform> screen_size: vec2<f32>;
+
+@group(1)@binding(0) var<uniform> center: vec2<f32>; 
+@group(1)@binding(1) var<uniform> size: vec2<f32>;
+@group(1)@binding(2) var<uniform> rotation: f32;
+@group(1)@binding(3) var<uniform> alpha: f32;
+@group(1)@binding(4) var<uniform> edges: vec2<f32>;
+@group(1)@binding(5) var<uniform> transform: mat3x3<f32>;
+
+
+@vertex
+fn vs_main(in: VertexInput) -> VertexOutput {
+    var out: VertexOutput;
+
+    // Calculate vertex position
+    var position = vertex_position(in.index);
+    out.clip_position = size * position*2.0;
+
+    // Scale and rotate the position
+    var scale = size * position;
+    var cos_angle = cos(rotation);
+    var sin_angle = sin(rotation);
+    var rotated_position = vec2(
+        scale.x * cos_angle - scale.y * sin_angle,
+        scale.x * sin_angle + scale.y * cos_angle
+    );
+    
+    // Translate to the new position
+    var new_position = center + rotated_position;
+    // Apply the transform style, w is left to the GPU so that
+    // perspective is interpolated correctly
+    var projected = transform * vec3(new_position, 1.0);
+    new_position = projected.xy / projected.z;
+    
+    // Convert to screen space
+    var screen_space = new_position / screen_size * 2.0 - 1.0;
+    var invert_y = vec2(screen_space.x, -screen_space.y);
+
+    out.position = vec4<f32>(invert_y * projected.z, 0.0, projected.z);
+
+    return out;
+}
+
+// Only the stencil is written, pixels outside of the rounded corners are discarded
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0)vec4<f32> {
+    var p = abs(in.clip_position);
+    var edge_size = edges.x * 2.0;
+    var s = size - edge_size;
+    if p.x >= s.x && p.y >= s.y && distance(p, s) >= edge_size {
+        discard;
+    }
+    return vec4<f32>(0.0);
+}
+
+
+
+fn vertex_position(vertex_index: u32) -> vec2<f32> {
+    // x: + + - - - +
+    // y: + - - - + +
+    return vec2<f32>((vec2(1u, 2u) + vertex_index) % vec2(6u) < vec2(3u))-0.5;
+}
@@ -6,6 +6,8 @@ struct VertexOutput {
     @builtin(position) position: vec4<f32>,
     @location(0) v_grad_coords: vec2<f32>,
     @location(1) clip_position: vec2<f32>,
+    // Position before the transform style, gradient points are in the same space
+    @location(2) layout_position: vec2<f32>,
 }
 
 @group(0)@binding(0) var<uniform> screen_size: vec2<f32>;
@@ -15,6 +17,7 @@ struct VertexOutput {
 @group(1)@binding(2) var<uniform> rotation: f32;
 @group(1)@binding(3) var<uniform> alpha: f32;
 @group(1)@binding(4) var<uniform> edges: vec2<f32>;
+@group(1)@binding(5) var<uniform> transform: mat3x3<f32>;
 
 @group(2)@binding(0) var<uniform> center_color: vec4<f32>;
 @group(2)@binding(1) var<uniform> center_pos: vec2<f32>;
@@ -42,12 +45,17 @@ fn vs_main(in: VertexInput) -> VertexOutput {
 
     // Translate to the new position
     var new_position = center + rotated_position;
+    out.layout_position = new_position;
+    // Apply the transform style, w is left to the GPU so that
+    // perspective is interpolated correctly
+    var projected = transform * vec3(new_position, 1.0);
+    new_position = projected.xy / projected.z;
 
     // Convert to screen space (-1.0 to 1.0)
     var screen_space = new_position / screen_size * 2.0 - 1.0;
     var invert_y = vec2(screen_space.x, -screen_space.y);
 
-    out.position = vec4<f32>(invert_y, 0.0, 1.0); // Screen-space positioning
+    out.position = vec4<f32>(invert_y * projected.z, 0.0, projected.z); // Screen-space positioning
 
     return out;
 }
@@ -58,7 +66,7 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     var p = abs(in.clip_position);
     var edge_size = edges.x * 2.0;
     var s = size - edge_size;
-    var color = mix(center_color, outer_color, distance(center_pos, in.position.xy) / distance(center_pos, outer_pos));
+    var color = mix(center_color, outer_color, distance(center_pos, in.layout_position) / distance(center_pos, outer_pos));
     if p.x < s.x || p.y < s.y {
         return vec4<f32>(color.rgb, color.a*alpha);
     }
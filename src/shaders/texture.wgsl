@@ -15,6 +15,7 @@ struct VertexOutput {
 @group(1)@binding(2) var<uniform> rotation: f32;
 @group(1)@binding(3) var<uniform> alpha: f32;
 @group(1)@binding(4) var<uniform> edges: vec2<f32>;
+@group(1)@binding(5) var<uniform> transform: mat3x3<f32>;
 
 @group(2)@binding(0) var t_diffuse: texture_2d<f32>;
 @group(2)@binding(1) var t_sampler: sampler;
@@ -39,12 +40,16 @@ fn vs_main(in: VertexInput) -> VertexOutput {
     
     // Translate to the new position
     var new_position = center + rotated_position;
+    // Apply the transform style, w is left to the GPU so that
+    // perspective is interpolated correctly
+    var projected = transform * vec3(new_position, 1.0);
+    new_position = projected.xy / projected.z;
     
     // Convert to screen space
     var screen_space = new_position / screen_size * 2.0 - 1.0;
     var invert_y = vec2(screen_space.x, -screen_space.y);
 
-    out.position = vec4<f32>(invert_y, 0.0, 1.0);
+    out.position = vec4<f32>(invert_y * projected.z, 0.0, projected.z);
 
     return out;
 }
@@ -0,0 +1,347 @@
+//! CPU renderer that works without a GPU
+//!
+//! Draws the same things as the wgpu pipelines into an `image::RgbaImage`.
+//! The math mirrors the shaders in `src/shaders`, colors are blended in linear
+//! space and stored as sRGB just like in the `Bgra8UnormSrgb` target the
+//! pipelines render into, so the output can be compared against GPU screenshots.
+
+use std::sync::OnceLock;
+
+use image::{Rgba, RgbaImage};
+
+use crate::{
+    display_list::{self, DrawCommand, Fill},
+    render::RenderElementData,
+    rotate_point,
+    styles::{Color, Rectangle},
+    Gui, Point,
+};
+
+/// Renders a `Gui` into an image on the CPU
+///
+/// ```ignore
+/// let mut gui: Gui<()> = Gui::new_headless((1280, 720));
+/// // add elements ...
+/// gui.update();
+/// let image = SoftwareRenderer::new().render(&mut gui);
+/// image.save("hud.png")?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct SoftwareRenderer {
+    /// Color the image is cleared with before drawing
+    pub clear_color: Color,
+}
+
+impl Default for SoftwareRenderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SoftwareRenderer {
+    pub fn new() -> Self {
+        Self {
+            clear_color: Color::BLACK,
+        }
+    }
+
+    pub fn with_clear_color(mut self, color: Color) -> Self {
+        self.clear_color = color;
+        self
+    }
+
+    /// Draws the `Gui` as it was laid out by the last `update`
+    pub fn render<Msg: Clone>(&self, gui: &mut Gui<Msg>) -> RgbaImage {
+        let (width, height) = gui.size;
+        let mut target = RgbaImage::from_pixel(width, height, encode(self.clear_color.into()));
+        let screen = Point::new(width as f32, height as f32);
+        let mut font = gui.font_system.take().unwrap();
+        let mut swash = gui.swash_cache.take().unwrap();
+        let mut clips: Vec<Clip> = vec![Clip {
+            scissor: gui.letterbox(),
+            masks: Vec::new(),
+        }];
+        for command in gui.display_list() {
+            let clip = clips.last().cloned().unwrap_or_default();
+            let clip = &clip;
+            match command {
+                DrawCommand::Quad { data, fill, .. } => match fill {
+                    Fill::Texture(texture) => {
+                        if let Some(image) = texture.image().as_ref() {
+                            draw_texture(&mut target, clip, &data, image)
+                        }
+                    }
+                    Fill::RadialGradient(grad) => fill_quad(&mut target, clip, &data, |fragment| {
+                        let t = fragment.pixel.distance(grad.center.into())
+                            / Point::from(grad.center).distance(grad.outer.into());
+                        let color = mix(grad.center_color.into(), grad.outer_color.into(), t);
+                        with_alpha(color, data.alpha * edges(fragment.position, &data))
+                    }),
+                    Fill::LinearGradient(grad) => {
+                        let start = [
+                            grad.start[0] / screen.x * 2.0,
+                            grad.start[1] / screen.y * 2.0,
+                        ];
+                        let end = [grad.end[0] / screen.x * 2.0, grad.end[1] / screen.y * 2.0];
+                        let dir = [end[0] - start[0], end[1] - start[1]];
+                        fill_quad(&mut target, clip, &data, |fragment| {
+                            let uv = [fragment.position.x + 0.5, fragment.position.y + 0.5];
+                            let t = ((uv[0] - start[0]) * dir[0] + (uv[1] - start[1]) * dir[1])
+                                / (dir[0] * dir[0] + dir[1] * dir[1]);
+                            let t = if t.is_nan() { 0.0 } else { t.clamp(0.0, 1.0) };
+                            let color = mix(grad.start_color.into(), grad.end_color.into(), t);
+                            with_alpha(color, data.alpha)
+                        })
+                    }
+                    Fill::Color(color) => fill_quad(&mut target, clip, &data, |fragment| {
+                        with_alpha(color.into(), data.alpha * edges(fragment.position, &data))
+                    }),
+                },
+                DrawCommand::Text { key, data, .. } => {
+                    let text = gui
+                        .elements
+                        .get_mut(key)
+                        .and_then(|element| element.rasterize_text(&mut font, &mut swash));
+                    if let Some(text) = text {
+                        draw_texture(&mut target, clip, &data, &text.to_rgba8());
+                    }
+                }
+                DrawCommand::PushClip { data, .. } => {
+                    let rect = display_list::bounds(&data);
+                    let mut clip = clip.clone();
+                    clip.scissor = Some(match clip.scissor {
+                        Some(scissor) => display_list::intersect(&scissor, &rect),
+                        None => rect,
+                    });
+                    if display_list::needs_stencil(&data) {
+                        clip.masks.push(data);
+                    }
+                    clips.push(clip);
+                }
+                DrawCommand::PopClip => {
+                    clips.pop();
+                }
+            }
+        }
+        gui.font_system = Some(font);
+        gui.swash_cache = Some(swash);
+        target
+    }
+}
+
+/// Part of the target that can be drawn to
+///
+/// Clips are always exact, like in a `Gui` created with a stencil buffer
+#[derive(Debug, Clone, Default)]
+struct Clip {
+    /// Bounding box of all clips
+    scissor: Option<Rectangle>,
+    /// Rotated or rounded clips
+    masks: Vec<RenderElementData>,
+}
+
+/// Input of a fragment
+struct Fragment {
+    /// Position inside the element, both axes go from -0.5 to 0.5
+    position: Point,
+    /// Center of the pixel in layout coordinates, before the `transform` style
+    pixel: Point,
+}
+
+/// Runs `shade` for every pixel covered by the element and blends the result into `target`
+///
+/// Pixels outside `clip` are skipped, the scissor is snapped to whole pixels
+/// like a scissor rect.
+fn fill_quad(
+    target: &mut RgbaImage,
+    clip: &Clip,
+    data: &RenderElementData,
+    shade: impl Fn(&Fragment) -> [f32; 4],
+) {
+    let (width, height) = (data.size[0], data.size[1]);
+    if width <= 0.0 || height <= 0.0 {
+        return;
+    }
+    let center = Point::from(data.center);
+    let inverse = match data.transform.inverse() {
+        Some(inverse) => inverse,
+        None => return,
+    };
+    let mut bounds = display_list::bounds(data);
+    if let Some(clip) = &clip.scissor {
+        let start = Point::new(clip.position.x.floor(), clip.position.y.floor());
+        let clip = Rectangle {
+            position: start,
+            size: Point::new(
+                (clip.position.x + clip.size.x).ceil() - start.x,
+                (clip.position.y + clip.size.y).ceil() - start.y,
+            ),
+        };
+        bounds = display_list::intersect(&bounds, &clip);
+    }
+    let x_range = bounds.position.x.floor().max(0.0) as u32
+        ..((bounds.position.x + bounds.size.x).ceil().max(0.0) as u32).min(target.width());
+    let y_range = bounds.position.y.floor().max(0.0) as u32
+        ..((bounds.position.y + bounds.size.y).ceil().max(0.0) as u32).min(target.height());
+    for y in y_range {
+        for x in x_range.clone() {
+            let pixel = Point::new(x as f32 + 0.5, y as f32 + 0.5);
+            let layout = inverse.apply(pixel);
+            let local = rotate_point(
+                Point::new(layout.x - center.x, layout.y - center.y),
+                Point::new(0.0, 0.0),
+                -data.rotation,
+            );
+            let position = Point::new(local.x / width, local.y / height);
+            if position.x < -0.5 || position.x >= 0.5 || position.y < -0.5 || position.y >= 0.5 {
+                continue;
+            }
+            if !clip
+                .masks
+                .iter()
+                .all(|mask| display_list::clip_contains(mask, pixel))
+            {
+                continue;
+            }
+            let color = shade(&Fragment {
+                position,
+                pixel: layout,
+            });
+            blend(target.get_pixel_mut(x, y), color);
+        }
+    }
+}
+
+fn draw_texture(
+    target: &mut RgbaImage,
+    clip: &Clip,
+    data: &RenderElementData,
+    texture: &RgbaImage,
+) {
+    if texture.width() == 0 || texture.height() == 0 {
+        return;
+    }
+    // the sampler magnifies linearly and minifies with the nearest texel
+    let magnified =
+        texture.width() as f32 <= data.size[0] && texture.height() as f32 <= data.size[1];
+    fill_quad(target, clip, data, |fragment| {
+        let uv = Point::new(fragment.position.x + 0.5, fragment.position.y + 0.5);
+        let color = if magnified {
+            sample_linear(texture, uv)
+        } else {
+            sample_nearest(texture, uv)
+        };
+        with_alpha(color, data.alpha * edges(fragment.position, data))
+    });
+}
+
+/// Rounding and smoothing of the corners, same as `fs_main` in `color.wgsl`
+fn edges(position: Point, data: &RenderElementData) -> f32 {
+    let size = data.size;
+    let p = [
+        (size[0] * position.x * 2.0).abs(),
+        (size[1] * position.y * 2.0).abs(),
+    ];
+    let edge_size = data.edges[0] * 2.0;
+    let s = [size[0] - edge_size, size[1] - edge_size];
+    if p[0] < s[0] || p[1] < s[1] {
+        return 1.0;
+    }
+    let dist = Point::from(p).distance(s.into());
+    if dist < edge_size {
+        return 1.0;
+    }
+    let glow = 1.0 - ((dist - edge_size) / data.edges[1]);
+    if glow.is_nan() {
+        0.0
+    } else {
+        glow.clamp(0.0, 1.0)
+    }
+}
+
+fn sample_nearest(texture: &RgbaImage, uv: Point) -> [f32; 4] {
+    let x = ((uv.x * texture.width() as f32) as i64).clamp(0, texture.width() as i64 - 1);
+    let y = ((uv.y * texture.height() as f32) as i64).clamp(0, texture.height() as i64 - 1);
+    decode(*texture.get_pixel(x as u32, y as u32))
+}
+
+fn sample_linear(texture: &RgbaImage, uv: Point) -> [f32; 4] {
+    let x = uv.x * texture.width() as f32 - 0.5;
+    let y = uv.y * texture.height() as f32 - 0.5;
+    let (x0, y0) = (x.floor(), y.floor());
+    let (fx, fy) = (x - x0, y - y0);
+    let texel = |x: f32, y: f32| {
+        let x = (x as i64).clamp(0, texture.width() as i64 - 1);
+        let y = (y as i64).clamp(0, texture.height() as i64 - 1);
+        decode(*texture.get_pixel(x as u32, y as u32))
+    };
+    let top = mix(texel(x0, y0), texel(x0 + 1.0, y0), fx);
+    let bottom = mix(texel(x0, y0 + 1.0), texel(x0 + 1.0, y0 + 1.0), fx);
+    mix(top, bottom, fy)
+}
+
+fn mix(a: [f32; 4], b: [f32; 4], t: f32) -> [f32; 4] {
+    [
+        a[0] + (b[0] - a[0]) * t,
+        a[1] + (b[1] - a[1]) * t,
+        a[2] + (b[2] - a[2]) * t,
+        a[3] + (b[3] - a[3]) * t,
+    ]
+}
+
+fn with_alpha(color: [f32; 4], alpha: f32) -> [f32; 4] {
+    [color[0], color[1], color[2], color[3] * alpha]
+}
+
+/// `wgpu::BlendState::ALPHA_BLENDING`
+fn blend(dst: &mut Rgba<u8>, src: [f32; 4]) {
+    let src = src.map(|c| if c.is_nan() { 0.0 } else { c.clamp(0.0, 1.0) });
+    let old = decode(*dst);
+    let a = src[3];
+    *dst = encode([
+        src[0] * a + old[0] * (1.0 - a),
+        src[1] * a + old[1] * (1.0 - a),
+        src[2] * a + old[2] * (1.0 - a),
+        a + old[3] * (1.0 - a),
+    ]);
+}
+
+/// Converts an sRGB pixel into linear color
+fn decode(pixel: Rgba<u8>) -> [f32; 4] {
+    static TABLE: OnceLock<[f32; 256]> = OnceLock::new();
+    let table = TABLE.get_or_init(|| {
+        std::array::from_fn(|i| {
+            let c = i as f32 / 255.0;
+            if c <= 0.04045 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        })
+    });
+    [
+        table[pixel[0] as usize],
+        table[pixel[1] as usize],
+        table[pixel[2] as usize],
+        pixel[3] as f32 / 255.0,
+    ]
+}
+
+/// Converts linear color into an sRGB pixel
+fn encode(color: [f32; 4]) -> Rgba<u8> {
+    let srgb = |c: f32| {
+        let c = c.clamp(0.0, 1.0);
+        let c = if c <= 0.0031308 {
+            c * 12.92
+        } else {
+            1.055 * c.powf(1.0 / 2.4) - 0.055
+        };
+        (c * 255.0).round() as u8
+    };
+    Rgba([
+        srgb(color[0]),
+        srgb(color[1]),
+        srgb(color[2]),
+        (color[3].clamp(0.0, 1.0) * 255.0).round() as u8,
+    ])
+}
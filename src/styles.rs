@@ -144,9 +144,17 @@ impl From<Color> for [f32; 4] {
     }
 }
 
+use std::fmt;
+use std::ops::{Add, Div, Mul, Sub};
+use std::str::FromStr;
 use std::sync::Arc;
 
-use crate::{rotate_point, texture::Texture, ElementTransform, Point};
+use crate::{
+    rotate_point,
+    texture::Texture,
+    transform::{Transform, TransformOp},
+    ElementKey, ElementTransform, Point,
+};
 
 pub struct StyleComponent<S> {
     pub(crate) style: S,
@@ -171,17 +179,27 @@ pub struct Styles {
     pub max_height: StyleComponent<Option<Values>>,
     pub min_height: StyleComponent<Option<Values>>,
     pub rotation: StyleComponent<Rotation>,
+    /// Point of the element that sits on `position` and that it rotates around
+    ///
+    /// `(0.0, 0.0)` is the top left corner and `(1.0, 1.0)` the bottom right one
+    pub pivot: StyleComponent<(f32, f32)>,
+    /// Moves, scales, skews, rotates and tilts the element and its children after layout
+    pub transform: StyleComponent<Transform>,
     pub bg_color: StyleComponent<Colors>,
     pub bg_texture: StyleComponent<Option<Arc<Texture>>>,
     pub bg_linear_gradient: StyleComponent<Option<LinearGradient>>,
     pub bg_radial_gradient: StyleComponent<Option<RadialGradient>>,
-    pub margin: StyleComponent<Values>,
-    pub padding: StyleComponent<Values>,
+    /// Space between the element and its container
+    pub margin: StyleComponent<Edges>,
+    /// Space between the element and its children
+    pub padding: StyleComponent<Edges>,
     pub alpha: StyleComponent<f32>,
     pub text_color: StyleComponent<Colors>,
     pub text_size: StyleComponent<Values>,
     pub edges_radius: StyleComponent<Values>,
     pub edges_smooth: StyleComponent<Values>,
+    /// Whether children are clipped to the element
+    pub overflow: StyleComponent<Overflow>,
     pub visible: bool,
     pub selectable: bool,
     pub z_index: i32,
@@ -201,16 +219,19 @@ impl Default for Styles {
             max_height: StyleComponent::new(None),
             min_height: StyleComponent::new(None),
             rotation: StyleComponent::new(Rotation::None),
+            pivot: StyleComponent::new((0.5, 0.5)),
+            transform: StyleComponent::new(Transform::new()),
             bg_color: StyleComponent::new(Colors::Rgba(0.0, 0.0, 0.0, 0.0)),
-            margin: StyleComponent::new(Values::Value(Value::Zero)),
-            padding: StyleComponent::new(Values::Value(Value::Zero)),
+            margin: StyleComponent::new(Edges::ZERO),
+            padding: StyleComponent::new(Edges::ZERO),
             text_color: StyleComponent::new(Colors::BLACK),
-            text_size: StyleComponent::new(Values::Value(Value::Pixel(50.0))),
+            text_size: StyleComponent::new(Values::Value(Value::Logical(50.0))),
             bg_texture: StyleComponent::new(None),
             bg_linear_gradient: StyleComponent::new(None),
             bg_radial_gradient: StyleComponent::new(None),
             edges_radius: StyleComponent::new(Values::Value(Value::Zero)),
             edges_smooth: StyleComponent::new(Values::Value(Value::Zero)),
+            overflow: StyleComponent::new(Overflow::Visible),
             alpha: StyleComponent::new(1.0),
             visible: true,
             selectable: false,
@@ -219,6 +240,174 @@ impl Default for Styles {
     }
 }
 
+impl Styles {
+    /// Returns elements that have to be laid out before the element
+    pub(crate) fn references(&self) -> Vec<ElementKey> {
+        let mut keys = Vec::new();
+        self.position.get().references(&mut keys);
+        for values in [
+            self.width.get(),
+            self.height.get(),
+            self.text_size.get(),
+            self.edges_radius.get(),
+            self.edges_smooth.get(),
+        ]
+        .into_iter()
+        .chain(self.min_width.get())
+        .chain(self.max_width.get())
+        .chain(self.min_height.get())
+        .chain(self.max_height.get())
+        {
+            values.references(&mut keys);
+        }
+        for edges in [self.margin.get(), self.padding.get()] {
+            for values in [&edges.top, &edges.right, &edges.bottom, &edges.left] {
+                values.references(&mut keys);
+            }
+        }
+        if let Some(gradient) = self.bg_linear_gradient.get() {
+            gradient.p1.position.references(&mut keys);
+            gradient.p2.position.references(&mut keys);
+        }
+        if let Some(gradient) = self.bg_radial_gradient.get() {
+            gradient.center.position.references(&mut keys);
+            gradient.outer.position.references(&mut keys);
+        }
+        for op in &self.transform.get().ops {
+            if let TransformOp::Translate(x, y) = op {
+                x.references(&mut keys);
+                y.references(&mut keys);
+            }
+        }
+        keys.sort_unstable_by_key(|key| (key.index, key.generation));
+        keys.dedup();
+        keys
+    }
+
+    /// Returns `min_width` and `max_width` resolved against `container`
+    pub fn width_limits(&self, container: &Container, view_port: &ViewPort) -> (f32, f32) {
+        limits(self.min_width.get(), self.max_width.get(), container, view_port)
+    }
+
+    /// Returns `min_height` and `max_height` resolved against `container`
+    pub fn height_limits(&self, container: &Container, view_port: &ViewPort) -> (f32, f32) {
+        limits(self.min_height.get(), self.max_height.get(), container, view_port)
+    }
+
+    /// Clamps `size` between the min and max width and height
+    ///
+    /// Min wins if it is larger than max
+    pub fn clamp_size(&self, size: Point, container: &Container, view_port: &ViewPort) -> Point {
+        let (min_width, max_width) = self.width_limits(container, view_port);
+        let (min_height, max_height) = self.height_limits(container, view_port);
+        Point::new(
+            size.x.min(max_width).max(min_width),
+            size.y.min(max_height).max(min_height),
+        )
+    }
+}
+
+fn limits(
+    min: &Option<Values>,
+    max: &Option<Values>,
+    container: &Container,
+    view_port: &ViewPort,
+) -> (f32, f32) {
+    (
+        min.as_ref()
+            .map(|min| min.calc(container, view_port))
+            .unwrap_or(0.0),
+        max.as_ref()
+            .map(|max| max.calc(container, view_port))
+            .unwrap_or(f32::INFINITY),
+    )
+}
+
+/// Sizes of the four sides of a box
+///
+/// Used for margin and padding, `Edges<f32>` holds the resolved sizes
+///
+/// ```ignore
+/// element.styles.padding.set(Edges::symmetric(
+///     Values::Value(Value::Pixel(4.0)),
+///     Values::Value(Value::Pixel(12.0)),
+/// ));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Edges<T = Values> {
+    pub top: T,
+    pub right: T,
+    pub bottom: T,
+    pub left: T,
+}
+
+impl<T: Clone> Edges<T> {
+    pub fn new(top: T, right: T, bottom: T, left: T) -> Self {
+        Self {
+            top,
+            right,
+            bottom,
+            left,
+        }
+    }
+
+    /// Same size on every side
+    pub fn all(value: T) -> Self {
+        Self {
+            top: value.clone(),
+            right: value.clone(),
+            bottom: value.clone(),
+            left: value,
+        }
+    }
+
+    /// `vertical` on top and bottom, `horizontal` on left and right
+    pub fn symmetric(vertical: T, horizontal: T) -> Self {
+        Self {
+            top: vertical.clone(),
+            right: horizontal.clone(),
+            bottom: vertical,
+            left: horizontal,
+        }
+    }
+}
+
+impl Edges {
+    pub const ZERO: Self = Self {
+        top: Values::Value(Value::Zero),
+        right: Values::Value(Value::Zero),
+        bottom: Values::Value(Value::Zero),
+        left: Values::Value(Value::Zero),
+    };
+
+    pub fn calc(&self, container: &Container, view_port: &ViewPort) -> Edges<f32> {
+        Edges {
+            top: self.top.calc(container, view_port),
+            right: self.right.calc(container, view_port),
+            bottom: self.bottom.calc(container, view_port),
+            left: self.left.calc(container, view_port),
+        }
+    }
+}
+
+impl Edges<f32> {
+    /// Returns `left + right`
+    pub fn horizontal(&self) -> f32 {
+        self.left + self.right
+    }
+
+    /// Returns `top + bottom`
+    pub fn vertical(&self) -> f32 {
+        self.top + self.bottom
+    }
+}
+
+impl From<Values> for Edges {
+    fn from(value: Values) -> Self {
+        Self::all(value)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Position {
     pub parent: Parent,
@@ -328,12 +517,17 @@ pub enum PositionValues {
     Bottom,
     BottomLeft,
     BottomRight,
+    /// Point inside of the parent, `(0.0, 0.0)` is the top left corner
+    /// and `(1.0, 1.0)` the bottom right one
+    Anchor(f32, f32),
 }
 
 #[derive(Debug, Clone)]
 pub enum Parent {
     ViewPort,
     Container,
+    /// Another element anywhere in the tree, after it was laid out
+    Element(ElementKey),
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -405,6 +599,19 @@ impl Colors {
     }
 }
 
+/// What happens to parts of children outside of the element
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum Overflow {
+    /// Children are drawn and hovered even outside of the element
+    #[default]
+    Visible,
+    /// Children are cut off at the edges of the element
+    ///
+    /// Rounded corners and rotation are respected when the `Gui` was created
+    /// with a stencil buffer, otherwise the clip is the bounding box.
+    Hidden,
+}
+
 #[derive(Debug, Default, Clone, Copy)]
 pub enum Rotation {
     Deg(f32),
@@ -417,13 +624,14 @@ pub enum Rotation {
 }
 
 impl Rotation {
+    /// Returns the rotation in radians
     pub fn calc(&self, container: &Container, _view_port: &ViewPort) -> f32 {
         match self {
-            Rotation::Deg(deg) => *deg + container.rotation,
-            Rotation::Rad(rad) => rad.to_degrees() + container.rotation,
+            Rotation::Deg(deg) => deg.to_radians() + container.rotation,
+            Rotation::Rad(rad) => *rad + container.rotation,
             Rotation::None => container.rotation,
-            Rotation::AbsDeg(deg) => *deg,
-            Rotation::AbsRad(rad) => rad.to_degrees(),
+            Rotation::AbsDeg(deg) => deg.to_radians(),
+            Rotation::AbsRad(rad) => *rad,
             Rotation::AbsNone => 0.0,
         }
     }
@@ -472,13 +680,18 @@ pub enum Value {
     /// This is the smallest space that the content fits into
     ///
     /// returns based on criteria:
-    /// 1. image, text - max(image, text)
-    /// 2. image - image
-    /// 3. text - text
-    /// 4. ___ - [1px, 1px]
+    /// 1. image, text, children - max(image, text, children)
+    /// 2. any of them - the largest one
+    /// 3. ___ - [1px, 1px]
     Content(RValue, Side),
-    /// Size in pixels
+    /// This is the space taken by another element after it was laid out
+    ///
+    /// Resolves to 0 if the element doesn't exist
+    Element(ElementKey, RValue, Side),
+    /// Size in physical pixels
     Pixel(f32),
+    /// Size in logical pixels, multiplied by the scale factor of the `Gui`
+    Logical(f32),
     /// Shortcut for `Value::Pixel(0.0)`
     Zero,
 }
@@ -554,15 +767,29 @@ impl<S> StyleComponent<S> {
 }
 
 impl Position {
+    /// Collects elements the position depends on
+    pub(crate) fn references(&self, keys: &mut Vec<ElementKey>) {
+        if let Parent::Element(key) = self.parent {
+            keys.push(key);
+        }
+        for offset in [&self.offset.0, &self.offset.1].into_iter().flatten() {
+            offset.references(keys);
+        }
+    }
+
     pub fn calc(&self, container: &Container, view_port: &ViewPort) -> Point {
         let cont = match self.parent {
             Parent::Container => container,
             Parent::ViewPort => &Container {
                 image: None,
+                text: None,
+                content: None,
                 position: Point::new(view_port.0 / 2.0, view_port.1 / 2.0),
                 rotation: 0.0,
                 size: Point::new(view_port.0, view_port.1),
+                elements: Vec::new(),
             },
+            Parent::Element(key) => container.element(key).unwrap_or(container),
         };
         let offset_x = self
             .offset
@@ -582,7 +809,7 @@ impl Position {
                     cont.position.x + $left + offset_x,
                     cont.position.y + $right + offset_y,
                 );
-                rotate_point(point, cont.position, -cont.rotation)
+                rotate_point(point, cont.position, cont.rotation)
             }};
         }
         let result = match &self.value {
@@ -595,6 +822,9 @@ impl Position {
             PositionValues::Bottom => corner!(0.0, cont.size.y * 0.5), // Centered horizontally, bottom vertically
             PositionValues::BottomLeft => corner!(-cont.size.x * 0.5, cont.size.y * 0.5),
             PositionValues::BottomRight => corner!(cont.size.x * 0.5, cont.size.y * 0.5),
+            PositionValues::Anchor(x, y) => {
+                corner!((x - 0.5) * cont.size.x, (y - 0.5) * cont.size.y)
+            }
         };
         result
     }
@@ -608,6 +838,462 @@ impl Values {
             Values::Function(fun) => fun.fun.calc(fun.value.calc(container, view_port)),
         }
     }
+
+    /// Collects elements the value depends on
+    pub(crate) fn references(&self, keys: &mut Vec<ElementKey>) {
+        match self {
+            Values::Expr(expr) => {
+                expr.left.references(keys);
+                expr.right.references(keys);
+            }
+            Values::Value(Value::Element(key, _, _)) => keys.push(*key),
+            Values::Value(_) => (),
+            Values::Function(fun) => fun.value.references(keys),
+        }
+    }
+
+    /// Returns the smaller of the two values
+    pub fn min(self, other: Values) -> Values {
+        Values::Expr(Box::new(Expression::new(self, Op::Min, other)))
+    }
+
+    /// Returns the larger of the two values
+    pub fn max(self, other: Values) -> Values {
+        Values::Expr(Box::new(Expression::new(self, Op::Max, other)))
+    }
+
+    /// Keeps the value between `min` and `max`, `min` wins if they cross
+    pub fn clamp(self, min: Values, max: Values) -> Values {
+        min.max(self.min(max))
+    }
+
+    /// Parses a CSS-like expression, `%` is a percentage of the container width
+    ///
+    /// Units:
+    /// - `px` or no unit - `Value::Pixel`
+    /// - `lpx` - `Value::Logical`
+    /// - `%`, `cqw`, `cqh`, `cqmin`, `cqmax` - percentage of the container
+    /// - `vw`, `vh`, `vmin`, `vmax` - percentage of the viewport
+    ///
+    /// Numbers can have a fraction and an exponent, like `.5` or `1e3`.
+    /// Operators `+ - * /` and parentheses work as usual. Functions are
+    /// `min` and `max` with any number of arguments, `clamp(min, value, max)`,
+    /// `pow`, `mod`, `round`, `floor`, `ceil`, `sqrt` and `abs`.
+    ///
+    /// ```ignore
+    /// let width = Values::parse("50% - 10px")?;
+    /// let panel = Values::parse("min(100vw, 400lpx)")?;
+    /// ```
+    pub fn parse(input: &str) -> Result<Values, ValuesError> {
+        Self::parse_for(input, Side::Width)
+    }
+
+    /// Parses like `Values::parse` with `%` measuring `percent` of the container
+    ///
+    /// Use `Side::Height` for heights
+    pub fn parse_for(input: &str, percent: Side) -> Result<Values, ValuesError> {
+        let mut parser = ValuesParser {
+            chars: input.char_indices().collect(),
+            index: 0,
+            end: input.len(),
+            percent,
+        };
+        parser.skip_whitespace();
+        if parser.peek().is_none() {
+            return Err(ValuesError::Empty);
+        }
+        let values = parser.sum()?;
+        match parser.peek() {
+            Some(found) => Err(ValuesError::Unexpected {
+                position: parser.position(),
+                found,
+                expected: "an operator",
+            }),
+            None => Ok(values),
+        }
+    }
+}
+
+impl From<Value> for Values {
+    fn from(value: Value) -> Self {
+        Values::Value(value)
+    }
+}
+
+impl FromStr for Values {
+    type Err = ValuesError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+macro_rules! values_op {
+    ($trait: ident, $fn: ident, $op: ident) => {
+        impl $trait for Values {
+            type Output = Values;
+
+            fn $fn(self, rhs: Values) -> Values {
+                Values::Expr(Box::new(Expression::new(self, Op::$op, rhs)))
+            }
+        }
+    };
+}
+
+values_op!(Add, add, Add);
+values_op!(Sub, sub, Sub);
+values_op!(Mul, mul, Mul);
+values_op!(Div, div, Div);
+
+/// Error returned when `Values` can't be parsed
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValuesError {
+    /// There is nothing to parse
+    Empty,
+    /// A character that doesn't fit at its place
+    Unexpected {
+        position: usize,
+        found: char,
+        expected: &'static str,
+    },
+    /// The input ends in the middle of an expression
+    UnexpectedEnd { expected: &'static str },
+    /// A number is followed by an unknown unit
+    UnknownUnit { position: usize, unit: String },
+    /// A function that doesn't exist is called
+    UnknownFunction { position: usize, name: String },
+    /// A function is called with the wrong number of arguments
+    ArgumentCount {
+        position: usize,
+        name: String,
+        expected: &'static str,
+        found: usize,
+    },
+    /// A parenthesis is never closed
+    UnclosedParenthesis { position: usize },
+}
+
+impl fmt::Display for ValuesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValuesError::Empty => write!(f, "expression is empty"),
+            ValuesError::Unexpected {
+                position,
+                found,
+                expected,
+            } => write!(
+                f,
+                "expected {} at position {}, found '{}'",
+                expected, position, found
+            ),
+            ValuesError::UnexpectedEnd { expected } => {
+                write!(f, "expected {} but the expression ended", expected)
+            }
+            ValuesError::UnknownUnit { position, unit } => write!(
+                f,
+                "unknown unit '{}' at position {}, expected px, lpx, %, vw, vh, vmin, vmax, cqw, cqh, cqmin or cqmax",
+                unit, position
+            ),
+            ValuesError::UnknownFunction { position, name } => write!(
+                f,
+                "unknown function '{}' at position {}, expected min, max, clamp, pow, mod, round, floor, ceil, sqrt or abs",
+                name, position
+            ),
+            ValuesError::ArgumentCount {
+                position,
+                name,
+                expected,
+                found,
+            } => write!(
+                f,
+                "'{}' at position {} takes {} but got {}",
+                name, position, expected, found
+            ),
+            ValuesError::UnclosedParenthesis { position } => {
+                write!(f, "parenthesis opened at position {} is never closed", position)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ValuesError {}
+
+/// Recursive descent parser behind `Values::parse`
+struct ValuesParser {
+    chars: Vec<(usize, char)>,
+    index: usize,
+    /// Byte length of the input
+    end: usize,
+    percent: Side,
+}
+
+impl ValuesParser {
+    fn peek(&self) -> Option<char> {
+        self.peek_at(0)
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.index + offset).map(|(_, c)| *c)
+    }
+
+    /// Error for the next character, or for the end of the input
+    fn unexpected(&self, expected: &'static str) -> ValuesError {
+        match self.peek() {
+            Some(found) => ValuesError::Unexpected {
+                position: self.position(),
+                found,
+                expected,
+            },
+            None => ValuesError::UnexpectedEnd { expected },
+        }
+    }
+
+    /// Byte offset of the next character
+    fn position(&self) -> usize {
+        self.chars
+            .get(self.index)
+            .map(|(position, _)| *position)
+            .unwrap_or(self.end)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.index += 1;
+        }
+    }
+
+    /// Takes characters while `accept` returns true
+    fn take(&mut self, accept: impl Fn(char) -> bool) -> String {
+        let mut taken = String::new();
+        while let Some(c) = self.peek().filter(|c| accept(*c)) {
+            taken.push(c);
+            self.index += 1;
+        }
+        taken
+    }
+
+    /// Terms joined by `+` and `-`
+    fn sum(&mut self) -> Result<Values, ValuesError> {
+        let mut left = self.product()?;
+        loop {
+            let op = match self.peek() {
+                Some('+') => Op::Add,
+                Some('-') => Op::Sub,
+                _ => return Ok(left),
+            };
+            self.index += 1;
+            self.skip_whitespace();
+            left = Values::Expr(Box::new(Expression::new(left, op, self.product()?)));
+        }
+    }
+
+    /// Factors joined by `*` and `/`
+    fn product(&mut self) -> Result<Values, ValuesError> {
+        let mut left = self.factor()?;
+        loop {
+            let op = match self.peek() {
+                Some('*') => Op::Mul,
+                Some('/') => Op::Div,
+                _ => return Ok(left),
+            };
+            self.index += 1;
+            self.skip_whitespace();
+            left = Values::Expr(Box::new(Expression::new(left, op, self.factor()?)));
+        }
+    }
+
+    /// Number, parenthesized expression, function call or negation of one,
+    /// followed by whitespace
+    fn factor(&mut self) -> Result<Values, ValuesError> {
+        let position = self.position();
+        let values = match self.peek() {
+            None => {
+                return Err(ValuesError::UnexpectedEnd {
+                    expected: "a value",
+                })
+            }
+            Some('-') => {
+                self.index += 1;
+                self.skip_whitespace();
+                return Ok(Values::Value(Value::Zero) - self.factor()?);
+            }
+            Some('(') => {
+                self.index += 1;
+                self.skip_whitespace();
+                let values = self.sum()?;
+                if self.peek() != Some(')') {
+                    return Err(ValuesError::UnclosedParenthesis { position });
+                }
+                self.index += 1;
+                values
+            }
+            Some(c) if c.is_ascii_digit() || c == '.' => self.number()?,
+            Some(c) if c.is_alphabetic() => self.function()?,
+            Some(found) => {
+                return Err(ValuesError::Unexpected {
+                    position,
+                    found,
+                    expected: "a value",
+                })
+            }
+        };
+        self.skip_whitespace();
+        Ok(values)
+    }
+
+    /// Number with a unit
+    ///
+    /// Digits with an optional fraction and exponent, like `12`, `.5` or `1.5e3`
+    fn number(&mut self) -> Result<Values, ValuesError> {
+        let mut digits = self.take(|c| c.is_ascii_digit());
+        if self.peek() == Some('.') {
+            self.index += 1;
+            let fraction = self.take(|c| c.is_ascii_digit());
+            if fraction.is_empty() {
+                return Err(self.unexpected("a digit after '.'"));
+            }
+            digits = format!("{}.{}", digits, fraction);
+        }
+        let sign = matches!(self.peek_at(1), Some('+' | '-')) as usize;
+        if matches!(self.peek(), Some('e' | 'E'))
+            && self.peek_at(1 + sign).is_some_and(|c| c.is_ascii_digit())
+        {
+            digits.push('e');
+            if sign == 1 {
+                digits.extend(self.peek_at(1));
+            }
+            self.index += 1 + sign;
+            digits.push_str(&self.take(|c| c.is_ascii_digit()));
+        }
+        let number = digits
+            .parse::<f32>()
+            .expect("only digits, one '.' and an exponent are taken");
+        let unit_position = self.position();
+        let unit = match self.peek() {
+            Some('%') => {
+                self.index += 1;
+                "%".to_string()
+            }
+            _ => self.take(char::is_alphabetic),
+        };
+        let value = match unit.as_str() {
+            "" | "px" => Value::Pixel(number),
+            "lpx" => Value::Logical(number),
+            "%" => Value::Container(RValue::Percent(number), self.percent.clone()),
+            "cqw" => Value::Container(RValue::Percent(number), Side::Width),
+            "cqh" => Value::Container(RValue::Percent(number), Side::Height),
+            "cqmin" => Value::Container(RValue::Percent(number), Side::Min),
+            "cqmax" => Value::Container(RValue::Percent(number), Side::Max),
+            "vw" => Value::ViewPort(RValue::Percent(number), Side::Width),
+            "vh" => Value::ViewPort(RValue::Percent(number), Side::Height),
+            "vmin" => Value::ViewPort(RValue::Percent(number), Side::Min),
+            "vmax" => Value::ViewPort(RValue::Percent(number), Side::Max),
+            _ => {
+                return Err(ValuesError::UnknownUnit {
+                    position: unit_position,
+                    unit,
+                })
+            }
+        };
+        Ok(Values::Value(value))
+    }
+
+    /// Function call like `min(10px, 50%)`
+    fn function(&mut self) -> Result<Values, ValuesError> {
+        let position = self.position();
+        let name = self.take(char::is_alphanumeric);
+        self.skip_whitespace();
+        match self.peek() {
+            Some('(') => self.index += 1,
+            Some(found) => {
+                return Err(ValuesError::Unexpected {
+                    position: self.position(),
+                    found,
+                    expected: "'(' after the function name",
+                })
+            }
+            None => {
+                return Err(ValuesError::UnexpectedEnd {
+                    expected: "'(' after the function name",
+                })
+            }
+        }
+        self.skip_whitespace();
+        let mut arguments = Vec::new();
+        if self.peek() != Some(')') {
+            loop {
+                arguments.push(self.sum()?);
+                match self.peek() {
+                    Some(',') => {
+                        self.index += 1;
+                        self.skip_whitespace();
+                    }
+                    Some(')') => break,
+                    Some(found) => {
+                        return Err(ValuesError::Unexpected {
+                            position: self.position(),
+                            found,
+                            expected: "',' or ')'",
+                        })
+                    }
+                    None => return Err(ValuesError::UnclosedParenthesis { position }),
+                }
+            }
+        }
+        self.index += 1;
+
+        let count = |expected: &'static str, ok: bool| match ok {
+            true => Ok(()),
+            false => Err(ValuesError::ArgumentCount {
+                position,
+                name: name.clone(),
+                expected,
+                found: arguments.len(),
+            }),
+        };
+        let fun = match name.as_str() {
+            "min" | "max" => {
+                count("at least one argument", !arguments.is_empty())?;
+                let op = if name == "min" { Op::Min } else { Op::Max };
+                return Ok(arguments
+                    .into_iter()
+                    .reduce(|a, b| Values::Expr(Box::new(Expression::new(a, op.clone(), b))))
+                    .unwrap_or(Values::Value(Value::Zero)));
+            }
+            "clamp" => {
+                count("3 arguments", arguments.len() == 3)?;
+                let mut arguments = arguments.into_iter();
+                let (min, value, max) = (
+                    arguments.next().unwrap(),
+                    arguments.next().unwrap(),
+                    arguments.next().unwrap(),
+                );
+                return Ok(value.clamp(min, max));
+            }
+            "pow" | "mod" => {
+                count("2 arguments", arguments.len() == 2)?;
+                let op = if name == "pow" { Op::Pow } else { Op::Mod };
+                let mut arguments = arguments.into_iter();
+                let (left, right) = (arguments.next().unwrap(), arguments.next().unwrap());
+                return Ok(Values::Expr(Box::new(Expression::new(left, op, right))));
+            }
+            "round" => Functions::Round,
+            "floor" => Functions::Floor,
+            "ceil" => Functions::Ceil,
+            "sqrt" => Functions::Sqrt,
+            "abs" => Functions::Abs,
+            _ => return Err(ValuesError::UnknownFunction { position, name }),
+        };
+        count("1 argument", arguments.len() == 1)?;
+        let value = arguments.pop().unwrap();
+        Ok(Values::Function(Box::new(Function::new(fun, value))))
+    }
+}
+
+impl Function {
+    pub fn new(fun: Functions, value: Values) -> Self {
+        Self { value, fun }
+    }
 }
 
 impl Functions {
@@ -623,6 +1309,10 @@ impl Functions {
 }
 
 impl Expression {
+    pub fn new(left: Values, op: Op, right: Values) -> Self {
+        Self { left, right, op }
+    }
+
     pub fn calc(&self, contaner: &Container, view_port: &ViewPort) -> f32 {
         let left = self.left.calc(contaner, view_port);
         let right = self.right.calc(contaner, view_port);
@@ -656,9 +1346,20 @@ impl Value {
                 Some(img) => r_value.calc(side.get_size(img.size.x, img.size.y)),
                 None => r_value.calc(side.get_size(contaner.size.x, contaner.size.y)),
             },
-            Value::Text(_r_value, _side) => todo!("Ouch thats gonna take a while"),
-            Value::Content(_r_value, _side) => todo!("Ouch thats gonna take a while"),
+            Value::Text(r_value, side) => match &contaner.text {
+                Some(text) => r_value.calc(side.get_size(text.size.x, text.size.y)),
+                None => 0.0,
+            },
+            Value::Content(r_value, side) => match &contaner.content {
+                Some(content) => r_value.calc(side.get_size(content.size.x, content.size.y)),
+                None => r_value.calc(side.get_size(1.0, 1.0)),
+            },
+            Value::Element(key, r_value, side) => match contaner.element(*key) {
+                Some(element) => r_value.calc(side.get_size(element.size.x, element.size.y)),
+                None => 0.0,
+            },
             Value::Pixel(num) => *num,
+            Value::Logical(num) => num * view_port.2,
             Value::Zero => 0.0,
         }
     }
@@ -737,12 +1438,30 @@ pub struct ColorPoint {
     pub color: Colors,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub struct Container {
     pub position: Point,
     pub size: Point,
+    /// Rotation in radians
     pub rotation: f32,
+    /// Size of the background texture
     pub image: Option<Rectangle>,
+    /// Size of the shaped text
+    pub text: Option<Rectangle>,
+    /// Smallest size that fits the image, text and children
+    pub content: Option<Rectangle>,
+    /// Elements referenced by `Value::Element` and `Parent::Element`
+    pub elements: Vec<(ElementKey, Container)>,
+}
+
+impl Container {
+    /// Returns the space taken by a referenced element
+    pub fn element(&self, key: ElementKey) -> Option<&Container> {
+        self.elements
+            .iter()
+            .find(|(element, _)| *element == key)
+            .map(|(_, container)| container)
+    }
 }
 
 impl From<ElementTransform> for Container {
@@ -752,12 +1471,71 @@ impl From<ElementTransform> for Container {
             size: transform.scale,
             rotation: transform.rotation,
             image: None,
+            text: None,
+            content: None,
+            elements: Vec::new(),
         }
     }
 }
 
+/// Width and height of the window in physical pixels and its scale factor
+///
+/// With a `ScaleMode` other than `ConstantPixelSize` this is the part of the
+/// window the layout fills and the physical pixels per logical pixel
 #[derive(Debug, Clone, Copy)]
-pub struct ViewPort(pub f32, pub f32);
+pub struct ViewPort(pub f32, pub f32, pub f32);
+
+/// How the `Gui` adapts to the size of the window
+///
+/// Only `Value::Logical` sizes scale, `Value::Pixel` stays in physical pixels
+/// and percentages follow the canvas. The scale factor of the `Gui` is used
+/// as is with `ConstantPixelSize`, the other modes replace it instead of
+/// multiplying it: the window size they scale from is already in physical
+/// pixels, so the DPI of the screen is part of it.
+///
+/// ```ignore
+/// // HUD designed for 1080p, one logical pixel is one pixel at 1920x1080
+/// gui.set_scale_mode(ScaleMode::ScaleWithScreen {
+///     reference: (1920.0, 1080.0),
+///     fit: ScreenMatch::Expand,
+/// });
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ScaleMode {
+    /// Logical pixels are as big as the scale factor of the `Gui` says
+    #[default]
+    ConstantPixelSize,
+    /// Logical pixels grow and shrink with the window
+    ///
+    /// `Value::Logical` sizes designed for the reference resolution keep
+    /// their proportions in any window
+    ScaleWithScreen {
+        /// Width and height the layout is designed for
+        reference: (f32, f32),
+        fit: ScreenMatch,
+    },
+    /// Layout fills the largest box with the aspect ratio of the reference
+    /// resolution, centered in the window
+    ///
+    /// Logical pixels scale like with `ScreenMatch::Expand`, nothing is drawn
+    /// or hovered outside of the box
+    Letterbox {
+        /// Width and height the layout is designed for
+        reference: (f32, f32),
+    },
+}
+
+/// Side of the window that `ScaleMode::ScaleWithScreen` follows
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ScreenMatch {
+    /// Width of the reference resolution always spans the window
+    Width,
+    /// Height of the reference resolution always spans the window
+    Height,
+    /// Reference resolution always fits, the window can show more
+    #[default]
+    Expand,
+}
 
 #[derive(Debug, Clone, Copy)]
 pub struct Rectangle {
@@ -1,10 +1,27 @@
 //! Minimalistic module for textures
 
 
-use image::GenericImageView;
+use std::sync::{Mutex, MutexGuard, OnceLock};
 
+/// Texture on the GPU
+///
+/// The wgpu objects are reached through `gpu`, like
+/// `texture.gpu().map(|gpu| &gpu.bind_group)`. Textures made with
+/// `Texture::new` exist before there is a GPU, they are uploaded by
+/// `Gui::prepare`.
 #[derive(Debug)]
 pub struct Texture {
+    size: (u32, u32),
+    label: Option<String>,
+    /// Pixels waiting for the upload, or kept for the `SoftwareRenderer`
+    image: Mutex<Option<image::RgbaImage>>,
+    keep_image: bool,
+    gpu: OnceLock<GpuTexture>,
+}
+
+/// GPU side of a `Texture`
+#[derive(Debug)]
+pub struct GpuTexture {
     pub texture: wgpu::Texture,
     pub view: wgpu::TextureView,
     pub sampler: wgpu::Sampler,
@@ -85,6 +102,11 @@ impl Texture {
         Self::from_image(device, queue, &img, Some(label))
     }
 
+    /// Creates a texture and uploads it to the GPU right away
+    ///
+    /// No pixels are kept on the CPU, so the `SoftwareRenderer` leaves the
+    /// texture out. Use `Texture::new(..).keep_image()` for textures that
+    /// both renderers draw.
     pub fn from_image(
         device: &wgpu::Device,
         queue: &wgpu::Queue,
@@ -92,7 +114,86 @@ impl Texture {
         label: Option<&str>,
     ) -> Self {
         let rgba = img.to_rgba8();
-        let dimensions = img.dimensions();
+        let texture = Self {
+            size: rgba.dimensions(),
+            label: label.map(str::to_string),
+            image: Mutex::new(None),
+            keep_image: false,
+            gpu: OnceLock::new(),
+        };
+        let _ = texture
+            .gpu
+            .set(GpuTexture::from_rgba(device, queue, &rgba, label));
+        texture
+    }
+
+    /// Creates a texture without uploading it to the GPU
+    ///
+    /// The upload happens in `Gui::prepare` the first time the texture is
+    /// used, the pixels are dropped then unless `keep_image` was called.
+    pub fn new(img: &image::DynamicImage, label: Option<&str>) -> Self {
+        let rgba = img.to_rgba8();
+        Self {
+            size: rgba.dimensions(),
+            label: label.map(str::to_string),
+            image: Mutex::new(Some(rgba)),
+            keep_image: false,
+            gpu: OnceLock::new(),
+        }
+    }
+
+    /// Keeps the pixels on the CPU after the upload so the `SoftwareRenderer` can draw them
+    ///
+    /// Textures without pixels are left out by the `SoftwareRenderer`. Only
+    /// textures made with `Texture::new` have pixels to keep.
+    pub fn keep_image(mut self) -> Self {
+        self.keep_image = true;
+        self
+    }
+
+    /// Returns width and height of the texture in pixels
+    pub fn dimensions(&self) -> (u32, u32) {
+        self.size
+    }
+
+    /// Returns the pixels if they are on the CPU
+    pub(crate) fn image(&self) -> MutexGuard<'_, Option<image::RgbaImage>> {
+        self.image.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Returns the GPU side of the texture if it has been uploaded
+    pub fn gpu(&self) -> Option<&GpuTexture> {
+        self.gpu.get()
+    }
+
+    /// Uploads the texture to the GPU, does nothing if it is already there
+    pub(crate) fn upload(&self, device: &wgpu::Device, queue: &wgpu::Queue) {
+        if self.gpu.get().is_some() {
+            return;
+        }
+        let mut image = self.image();
+        if let Some(rgba) = image.as_ref() {
+            let _ = self.gpu.set(GpuTexture::from_rgba(
+                device,
+                queue,
+                rgba,
+                self.label.as_deref(),
+            ));
+        }
+        if !self.keep_image {
+            *image = None;
+        }
+    }
+}
+
+impl GpuTexture {
+    fn from_rgba(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        rgba: &image::RgbaImage,
+        label: Option<&str>,
+    ) -> Self {
+        let dimensions = rgba.dimensions();
 
         let size = wgpu::Extent3d {
             width: dimensions.0,
@@ -117,7 +218,7 @@ impl Texture {
                 mip_level: 0,
                 origin: wgpu::Origin3d::ZERO,
             },
-            &rgba,
+            rgba,
             wgpu::ImageDataLayout {
                 offset: 0,
                 bytes_per_row: Some(4 * dimensions.0),
@@ -138,7 +239,7 @@ impl Texture {
         });
 
         let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-            layout: &device.create_bind_group_layout(&Self::BIND_GROUP_LAYOUT),
+            layout: &device.create_bind_group_layout(&Texture::BIND_GROUP_LAYOUT),
             entries: &[
                 wgpu::BindGroupEntry {
                     binding: 0,
@@ -0,0 +1,369 @@
+//! Transforms applied after layout
+//!
+//! The `transform` style moves, scales, skews and rotates an element and its
+//! children without changing the space anything takes in the layout. It is
+//! applied around the `pivot` of the element, on top of the transforms of its
+//! ancestors. Drawing and hit testing both see the transformed element.
+//!
+//! Rotations around the x and y axes tilt the element out of the screen, add
+//! a `perspective` before them to make the far side smaller. Children are
+//! flattened into the plane of their parent, like with CSS `transform-style: flat`.
+
+use std::ops::Mul;
+
+use crate::{
+    styles::{Container, Values, ViewPort},
+    Point,
+};
+
+/// Projective 3x3 matrix
+///
+/// Maps `(x, y)` to `((a * x + c * y + e) / w, (b * x + d * y + f) / w)` where
+/// `w = g * x + h * y + i`. Without perspective `g` and `h` are 0 and `i` is 1,
+/// then it is the CSS `matrix(a, b, c, d, e, f)`. `first * second` applies
+/// `second` first.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Matrix {
+    pub a: f32,
+    pub b: f32,
+    pub c: f32,
+    pub d: f32,
+    pub e: f32,
+    pub f: f32,
+    pub g: f32,
+    pub h: f32,
+    pub i: f32,
+}
+
+impl Default for Matrix {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Matrix {
+    pub const IDENTITY: Self = Self {
+        a: 1.0,
+        b: 0.0,
+        c: 0.0,
+        d: 1.0,
+        e: 0.0,
+        f: 0.0,
+        g: 0.0,
+        h: 0.0,
+        i: 1.0,
+    };
+
+    pub fn translate(x: f32, y: f32) -> Self {
+        Self {
+            e: x,
+            f: y,
+            ..Self::IDENTITY
+        }
+    }
+
+    pub fn scale(x: f32, y: f32) -> Self {
+        Self {
+            a: x,
+            d: y,
+            ..Self::IDENTITY
+        }
+    }
+
+    /// Slants the x axis by `x` and the y axis by `y` radians
+    pub fn skew(x: f32, y: f32) -> Self {
+        Self {
+            b: y.tan(),
+            c: x.tan(),
+            ..Self::IDENTITY
+        }
+    }
+
+    /// Rotates clockwise on screen by `angle` radians, same as `Rotation`
+    pub fn rotate(angle: f32) -> Self {
+        let (sin, cos) = angle.sin_cos();
+        Self {
+            a: cos,
+            b: sin,
+            c: -sin,
+            d: cos,
+            ..Self::IDENTITY
+        }
+    }
+
+    /// Applies `self` around `origin` instead of `(0, 0)`
+    pub fn around(self, origin: Point) -> Self {
+        Self::translate(origin.x, origin.y) * self * Self::translate(-origin.x, -origin.y)
+    }
+
+    pub fn apply(&self, point: Point) -> Point {
+        let w = self.g * point.x + self.h * point.y + self.i;
+        Point::new(
+            (self.a * point.x + self.c * point.y + self.e) / w,
+            (self.b * point.x + self.d * point.y + self.f) / w,
+        )
+    }
+
+    /// Returns the matrix that undoes this one, `None` if it flattens everything onto a line
+    pub fn inverse(&self) -> Option<Self> {
+        let Self {
+            a,
+            b,
+            c,
+            d,
+            e,
+            f,
+            g,
+            h,
+            i,
+        } = *self;
+        // rows are (a c e), (b d f), (g h i)
+        let det = a * (d * i - f * h) - c * (b * i - f * g) + e * (b * h - d * g);
+        if det == 0.0 || !det.is_finite() {
+            return None;
+        }
+        Some(Self {
+            a: (d * i - f * h) / det,
+            b: (f * g - b * i) / det,
+            c: (e * h - c * i) / det,
+            d: (a * i - e * g) / det,
+            e: (c * f - e * d) / det,
+            f: (e * b - a * f) / det,
+            g: (b * h - d * g) / det,
+            h: (c * g - a * h) / det,
+            i: (a * d - c * b) / det,
+        })
+    }
+
+    pub fn is_identity(&self) -> bool {
+        *self == Self::IDENTITY
+    }
+
+    /// Returns true if rectangles stay axis-aligned rectangles
+    pub fn is_axis_aligned(&self) -> bool {
+        self.b == 0.0 && self.c == 0.0 && self.g == 0.0 && self.h == 0.0
+    }
+
+    /// Returns the matrix as a WGSL `mat3x3<f32>` uniform, columns are padded to 4 floats
+    pub(crate) fn to_mat3(self) -> [[f32; 4]; 3] {
+        [
+            [self.a, self.b, self.g, 0.0],
+            [self.c, self.d, self.h, 0.0],
+            [self.e, self.f, self.i, 0.0],
+        ]
+    }
+}
+
+impl Mul for Matrix {
+    type Output = Matrix;
+
+    fn mul(self, rhs: Matrix) -> Matrix {
+        Matrix {
+            a: self.a * rhs.a + self.c * rhs.b + self.e * rhs.g,
+            b: self.b * rhs.a + self.d * rhs.b + self.f * rhs.g,
+            c: self.a * rhs.c + self.c * rhs.d + self.e * rhs.h,
+            d: self.b * rhs.c + self.d * rhs.d + self.f * rhs.h,
+            e: self.a * rhs.e + self.c * rhs.f + self.e * rhs.i,
+            f: self.b * rhs.e + self.d * rhs.f + self.f * rhs.i,
+            g: self.g * rhs.a + self.h * rhs.b + self.i * rhs.g,
+            h: self.g * rhs.c + self.h * rhs.d + self.i * rhs.h,
+            i: self.g * rhs.e + self.h * rhs.f + self.i * rhs.i,
+        }
+    }
+}
+
+/// 4x4 matrix the transforms are composed in before the element is flattened
+///
+/// Rows are x, y, z and w, z points towards the viewer.
+#[derive(Debug, Clone, Copy)]
+struct Matrix4([[f32; 4]; 4]);
+
+impl Matrix4 {
+    const IDENTITY: Self = Self([
+        [1.0, 0.0, 0.0, 0.0],
+        [0.0, 1.0, 0.0, 0.0],
+        [0.0, 0.0, 1.0, 0.0],
+        [0.0, 0.0, 0.0, 1.0],
+    ]);
+
+    fn rotate_x(angle: f32) -> Self {
+        let (sin, cos) = angle.sin_cos();
+        let mut matrix = Self::IDENTITY;
+        matrix.0[1][1] = cos;
+        matrix.0[1][2] = -sin;
+        matrix.0[2][1] = sin;
+        matrix.0[2][2] = cos;
+        matrix
+    }
+
+    fn rotate_y(angle: f32) -> Self {
+        let (sin, cos) = angle.sin_cos();
+        let mut matrix = Self::IDENTITY;
+        matrix.0[0][0] = cos;
+        matrix.0[0][2] = sin;
+        matrix.0[2][0] = -sin;
+        matrix.0[2][2] = cos;
+        matrix
+    }
+
+    /// Viewer `distance` pixels in front of the screen, same as CSS `perspective()`
+    fn perspective(distance: f32) -> Self {
+        let mut matrix = Self::IDENTITY;
+        if distance > 0.0 {
+            matrix.0[3][2] = -1.0 / distance;
+        }
+        matrix
+    }
+
+    /// Returns the matrix for the `z = 0` plane
+    fn flatten(&self) -> Matrix {
+        let m = &self.0;
+        Matrix {
+            a: m[0][0],
+            b: m[1][0],
+            c: m[0][1],
+            d: m[1][1],
+            e: m[0][3],
+            f: m[1][3],
+            g: m[3][0],
+            h: m[3][1],
+            i: m[3][3],
+        }
+    }
+}
+
+impl From<Matrix> for Matrix4 {
+    fn from(matrix: Matrix) -> Self {
+        Self([
+            [matrix.a, matrix.c, 0.0, matrix.e],
+            [matrix.b, matrix.d, 0.0, matrix.f],
+            [0.0, 0.0, 1.0, 0.0],
+            [matrix.g, matrix.h, 0.0, matrix.i],
+        ])
+    }
+}
+
+impl Mul for Matrix4 {
+    type Output = Matrix4;
+
+    fn mul(self, rhs: Matrix4) -> Matrix4 {
+        Matrix4(std::array::from_fn(|row| {
+            std::array::from_fn(|column| (0..4).map(|k| self.0[row][k] * rhs.0[k][column]).sum())
+        }))
+    }
+}
+
+/// Transforms of the `transform` style
+///
+/// Like in CSS, the last one is applied to the element first. They are
+/// applied around `origin`, or around the `pivot` of the element if it is
+/// not set.
+///
+/// ```ignore
+/// // pulse while keeping the layout still
+/// button.styles.transform.set(Transform::new().scale(1.1, 1.1));
+/// // pop-up that slides in from above
+/// let above = Values::Value(Value::Container(RValue::Fraction(-1.0), Side::Height));
+/// popup.styles.transform.set(Transform::new().translate(Value::Zero.into(), above));
+/// // card half way through a flip
+/// card.styles.transform.set(Transform::new().perspective(800.0).rotate_y(FRAC_PI_4));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Transform {
+    pub ops: Vec<TransformOp>,
+    /// Point of the element the transforms are applied around
+    ///
+    /// `(0.0, 0.0)` is the top left corner and `(1.0, 1.0)` the bottom right one
+    pub origin: Option<(f32, f32)>,
+}
+
+#[derive(Debug, Clone)]
+pub enum TransformOp {
+    /// Moves the element, values are resolved against the element itself
+    Translate(Values, Values),
+    Scale(f32, f32),
+    /// Slants the x and y axes, in radians
+    Skew(f32, f32),
+    /// Rotates the element, in radians
+    Rotate(f32),
+    /// Tilts the element around the horizontal axis, in radians,
+    /// positive angles move the top away from the viewer
+    RotateX(f32),
+    /// Tilts the element around the vertical axis, in radians,
+    /// positive angles move the right side away from the viewer
+    RotateY(f32),
+    /// Distance of the viewer from the screen in pixels, transforms after it
+    /// that tilt the element are seen in perspective
+    Perspective(f32),
+}
+
+impl Transform {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn translate(mut self, x: Values, y: Values) -> Self {
+        self.ops.push(TransformOp::Translate(x, y));
+        self
+    }
+
+    pub fn scale(mut self, x: f32, y: f32) -> Self {
+        self.ops.push(TransformOp::Scale(x, y));
+        self
+    }
+
+    pub fn skew(mut self, x: f32, y: f32) -> Self {
+        self.ops.push(TransformOp::Skew(x, y));
+        self
+    }
+
+    pub fn rotate(mut self, angle: f32) -> Self {
+        self.ops.push(TransformOp::Rotate(angle));
+        self
+    }
+
+    pub fn rotate_x(mut self, angle: f32) -> Self {
+        self.ops.push(TransformOp::RotateX(angle));
+        self
+    }
+
+    pub fn rotate_y(mut self, angle: f32) -> Self {
+        self.ops.push(TransformOp::RotateY(angle));
+        self
+    }
+
+    pub fn perspective(mut self, distance: f32) -> Self {
+        self.ops.push(TransformOp::Perspective(distance));
+        self
+    }
+
+    pub fn with_origin(mut self, x: f32, y: f32) -> Self {
+        self.origin = Some((x, y));
+        self
+    }
+
+    /// Returns the transforms composed into one matrix
+    ///
+    /// `container` is the element itself
+    pub fn calc(&self, container: &Container, view_port: &ViewPort) -> Matrix {
+        self.ops
+            .iter()
+            .fold(Matrix4::IDENTITY, |matrix, op| {
+                matrix
+                    * match op {
+                        TransformOp::Translate(x, y) => Matrix::translate(
+                            x.calc(container, view_port),
+                            y.calc(container, view_port),
+                        )
+                        .into(),
+                        TransformOp::Scale(x, y) => Matrix::scale(*x, *y).into(),
+                        TransformOp::Skew(x, y) => Matrix::skew(*x, *y).into(),
+                        TransformOp::Rotate(angle) => Matrix::rotate(*angle).into(),
+                        TransformOp::RotateX(angle) => Matrix4::rotate_x(*angle),
+                        TransformOp::RotateY(angle) => Matrix4::rotate_y(*angle),
+                        TransformOp::Perspective(distance) => Matrix4::perspective(*distance),
+                    }
+            })
+            .flatten()
+    }
+}
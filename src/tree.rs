@@ -0,0 +1,104 @@
+//! Navigation of the `Element` tree
+
+use crate::{ElementKey, Gui};
+
+impl<Msg> Gui<Msg>
+where
+    Msg: Clone,
+{
+    /// Returns the parent of an element
+    pub fn parent_of(&self, key: ElementKey) -> Option<ElementKey> {
+        self.get_element(key)?
+            .parent
+            .filter(|parent| self.contains(*parent))
+    }
+
+    /// Iterates over direct children of an element
+    pub fn children_of(&self, key: ElementKey) -> impl Iterator<Item = ElementKey> {
+        self.get_element(key)
+            .map(|element| element.children.keys())
+            .unwrap_or_default()
+            .into_iter()
+    }
+
+    /// Iterates over parents of an element, starting with the closest one
+    pub fn ancestors(&self, key: ElementKey) -> Ancestors<'_, Msg> {
+        Ancestors {
+            gui: self,
+            current: self.parent_of(key),
+        }
+    }
+
+    /// Iterates over all elements below an element in depth-first order
+    ///
+    /// The element itself is not included
+    pub fn descendants(&self, key: ElementKey) -> Descendants<'_, Msg> {
+        let mut stack = self.children_of(key).collect::<Vec<_>>();
+        stack.reverse();
+        Descendants { gui: self, stack }
+    }
+
+    /// Iterates over other children of the element's parent
+    pub fn siblings(&self, key: ElementKey) -> impl Iterator<Item = ElementKey> {
+        self.parent_of(key)
+            .map(|parent| self.children_of(parent).collect::<Vec<_>>())
+            .unwrap_or_default()
+            .into_iter()
+            .filter(move |sibling| *sibling != key)
+    }
+}
+
+/// Iterator over parents of an element
+///
+/// Created by `Gui::ancestors`
+pub struct Ancestors<'a, Msg>
+where
+    Msg: Clone,
+{
+    gui: &'a Gui<Msg>,
+    current: Option<ElementKey>,
+}
+
+impl<'a, Msg> Iterator for Ancestors<'a, Msg>
+where
+    Msg: Clone,
+{
+    type Item = ElementKey;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let key = self.current?;
+        self.current = self.gui.parent_of(key);
+        Some(key)
+    }
+}
+
+/// Depth-first iterator over all elements below an element
+///
+/// Created by `Gui::descendants`
+pub struct Descendants<'a, Msg>
+where
+    Msg: Clone,
+{
+    gui: &'a Gui<Msg>,
+    stack: Vec<ElementKey>,
+}
+
+impl<'a, Msg> Iterator for Descendants<'a, Msg>
+where
+    Msg: Clone,
+{
+    type Item = ElementKey;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let key = loop {
+            let key = self.stack.pop()?;
+            if self.gui.contains(key) {
+                break key;
+            }
+        };
+        let first = self.stack.len();
+        self.stack.extend(self.gui.children_of(key));
+        self.stack[first..].reverse();
+        Some(key)
+    }
+}
@@ -31,6 +31,9 @@ pub fn event<Msg: Clone>(gui: &mut crate::Gui<Msg>, event: &WinitWindowEvent) {
             position: Point::new(position.x as f32, position.y as f32),
             last: Point::new(position.x as f32, position.y as f32),
         }),
+        WinitWindowEvent::ScaleFactorChanged { scale_factor, .. } => {
+            gui.set_scale_factor(*scale_factor as f32)
+        }
         WinitWindowEvent::MouseWheel {
             device_id: _,
             delta,
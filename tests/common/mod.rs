@@ -0,0 +1,30 @@
+//! Helpers shared by the integration tests
+#![allow(dead_code)]
+
+use rugui::{
+    styles::{Value, Values},
+    Element, ElementKey, Gui,
+};
+
+pub fn px(value: f32) -> Values {
+    Values::Value(Value::Pixel(value))
+}
+
+/// Adds an element with a fixed width and height
+pub fn sized<Msg: Clone>(gui: &mut Gui<Msg>, width: f32, height: f32) -> ElementKey {
+    let mut element = Element::new();
+    element.styles.width.set(px(width));
+    element.styles.height.set(px(height));
+    gui.add_element(element)
+}
+
+/// Returns x, y, width and height of the element's bounds
+pub fn rect<Msg: Clone>(gui: &Gui<Msg>, key: ElementKey) -> (f32, f32, f32, f32) {
+    let bounds = gui.element_rect(key).unwrap().bounds;
+    (
+        bounds.position.x,
+        bounds.position.y,
+        bounds.size.x,
+        bounds.size.y,
+    )
+}
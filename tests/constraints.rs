@@ -0,0 +1,161 @@
+use rugui::{
+    constraints::{Anchor, Attribute, Constraint, ConstraintLayout, Strength},
+    styles::{RValue, Side, Value, Values},
+    Children, Element, ElementKey, Gui,
+};
+
+mod common;
+
+use common::{px, rect, sized};
+
+fn layout(gui: &Gui<()>, key: ElementKey) -> &ConstraintLayout {
+    match &gui.get_element(key).unwrap().children {
+        Children::Constraints(layout) => layout,
+        _ => panic!("not a constraint layout"),
+    }
+}
+
+fn width(element: ElementKey) -> Attribute {
+    Attribute::new(element, Anchor::Width)
+}
+
+fn close(a: f32, b: f32) -> bool {
+    (a - b).abs() < 0.01
+}
+
+/// Makes an element with `layout` the entry, lays it out and returns its key
+fn laid_out(gui: &mut Gui<()>, layout: ConstraintLayout) -> ElementKey {
+    let root = gui.add_element(Element::new().with_children(Children::Constraints(layout)));
+    gui.set_entry(Some(root));
+    gui.update();
+    root
+}
+
+#[test]
+fn doc_example() {
+    let mut gui: Gui<()> = Gui::new_headless((1200, 600));
+    let sidebar = gui.add_element(Element::new());
+    let label = sized(&mut gui, 80.0, 20.0);
+    let slider = sized(&mut gui, 200.0, 20.0);
+    let quarter = Values::Value(Value::ViewPort(RValue::Fraction(0.25), Side::Width));
+    let root = laid_out(
+        &mut gui,
+        ConstraintLayout::new(vec![sidebar, label, slider])
+            .with_constraint(Constraint::equal(
+                Attribute::new(label, Anchor::Left),
+                Attribute::new(slider, Anchor::Left),
+            ))
+            .with_constraint(Constraint::greater_or_equal(width(sidebar), px(200.0)))
+            .with_constraint(
+                Constraint::equal(width(sidebar), quarter).with_strength(Strength::Strong),
+            )
+            .with_constraint(Constraint::equal(
+                Attribute::new(sidebar, Anchor::Right) + px(16.0),
+                Attribute::new(slider, Anchor::Left),
+            )),
+    );
+
+    let check = |gui: &Gui<()>, sidebar_width: f32| {
+        let (sidebar_x, _, sidebar_w, _) = rect(gui, sidebar);
+        let (label_x, _, label_w, _) = rect(gui, label);
+        let (slider_x, _, slider_w, _) = rect(gui, slider);
+        assert!(close(sidebar_w, sidebar_width), "{}", sidebar_w);
+        assert!(close(label_x, slider_x), "{} {}", label_x, slider_x);
+        assert!(close(sidebar_x + sidebar_w + 16.0, slider_x));
+        assert_eq!((label_w, slider_w), (80.0, 200.0));
+    };
+    check(&gui, 300.0);
+    assert!(!layout(&gui, root).has_conflict());
+
+    // a quarter is less than the required minimum
+    gui.set_size((600, 600));
+    gui.update();
+    check(&gui, 200.0);
+}
+
+#[test]
+fn inequalities_only_act_when_broken() {
+    let mut gui: Gui<()> = Gui::new_headless((400, 400));
+    let narrow = sized(&mut gui, 100.0, 50.0);
+    let wide = sized(&mut gui, 100.0, 50.0);
+    laid_out(
+        &mut gui,
+        ConstraintLayout::new(vec![narrow, wide])
+            .with_constraint(Constraint::less_or_equal(width(narrow), px(60.0)))
+            .with_constraint(Constraint::less_or_equal(width(wide), px(150.0))),
+    );
+
+    assert_eq!(rect(&gui, narrow).2, 60.0);
+    // centered with its preferred size
+    assert_eq!(rect(&gui, wide), (150.0, 175.0, 100.0, 50.0));
+}
+
+#[test]
+fn stronger_constraints_win() {
+    let mut gui: Gui<()> = Gui::new_headless((400, 400));
+    let child = sized(&mut gui, 100.0, 50.0);
+    let root = laid_out(
+        &mut gui,
+        ConstraintLayout::new(vec![child])
+            .with_constraint(
+                Constraint::equal(width(child), px(300.0)).with_strength(Strength::Weak),
+            )
+            .with_constraint(
+                Constraint::equal(width(child), px(200.0)).with_strength(Strength::Strong),
+            )
+            .with_constraint(
+                Constraint::equal(width(child), px(250.0)).with_strength(Strength::Medium),
+            ),
+    );
+
+    assert!(close(rect(&gui, child).2, 200.0));
+    assert!(!layout(&gui, root).has_conflict());
+}
+
+#[test]
+fn conflicting_required_constraints_still_beat_strong_ones() {
+    // the required constraints only allow widths from 200 to 300 together,
+    // the strong one picks the end closest to it
+    for (strong, expected) in [(100.0, 200.0), (400.0, 300.0)] {
+        let mut gui: Gui<()> = Gui::new_headless((400, 400));
+        let child = sized(&mut gui, 100.0, 50.0);
+        let root = laid_out(
+            &mut gui,
+            ConstraintLayout::new(vec![child])
+                .with_constraint(Constraint::equal(width(child), px(200.0)))
+                .with_constraint(Constraint::greater_or_equal(width(child), px(300.0)))
+                .with_constraint(
+                    Constraint::equal(width(child), px(strong)).with_strength(Strength::Strong),
+                ),
+        );
+
+        assert!(layout(&gui, root).has_conflict());
+        assert!(
+            close(rect(&gui, child).2, expected),
+            "{}",
+            rect(&gui, child).2
+        );
+
+        // stays the same while nothing changes
+        gui.update();
+        assert!(layout(&gui, root).has_conflict());
+        assert!(close(rect(&gui, child).2, expected));
+    }
+}
+
+#[test]
+fn constraints_on_other_elements_are_ignored() {
+    let mut gui: Gui<()> = Gui::new_headless((400, 400));
+    let child = sized(&mut gui, 100.0, 50.0);
+    let stranger = sized(&mut gui, 10.0, 10.0);
+    let root = laid_out(
+        &mut gui,
+        ConstraintLayout::new(vec![child])
+            .with_constraint(Constraint::equal(width(child), width(stranger)))
+            .with_constraint(Constraint::equal(width(child), px(120.0))),
+    );
+
+    assert!(layout(&gui, root).has_ignored_constraints());
+    assert!(!layout(&gui, root).has_conflict());
+    assert!(close(rect(&gui, child).2, 120.0));
+}
@@ -0,0 +1,143 @@
+use rugui::{
+    events::{EventTypes, WindowEvent},
+    styles::{Overflow, Parent, Position, PositionValues},
+    Children, Element, ElementKey, Gui, Point,
+};
+
+mod common;
+
+use common::px;
+
+fn square(gui: &mut Gui<&'static str>, position: PositionValues) -> ElementKey {
+    let mut element = Element::new();
+    element.styles.width.set(px(50.0));
+    element.styles.height.set(px(50.0));
+    element
+        .styles
+        .position
+        .set(Position::default().with_value(position));
+    element.events.listen(EventTypes::MouseEnter, "enter");
+    element.events.listen(EventTypes::MouseLeave, "leave");
+    gui.add_element(element)
+}
+
+fn messages(gui: &mut Gui<&'static str>) -> Vec<&'static str> {
+    std::iter::from_fn(|| gui.poll_event())
+        .map(|event| event.msg)
+        .collect()
+}
+
+fn move_mouse(gui: &mut Gui<&'static str>, x: f32, y: f32) {
+    gui.event(WindowEvent::MouseMove {
+        position: Point::new(x, y),
+        last: Point::new(0.0, 0.0),
+    });
+}
+
+#[test]
+fn layout_moves_element_under_the_mouse() {
+    let mut gui = Gui::new_headless((200, 100));
+    let square = square(&mut gui, PositionValues::Left);
+    let root = gui.add_element(Element::new().with_children(Children::Element(square)));
+    gui.set_entry(Some(root));
+    gui.update();
+    move_mouse(&mut gui, 190.0, 50.0);
+    gui.update();
+    assert!(messages(&mut gui).is_empty());
+
+    if let Some(element) = gui.get_element_mut(square) {
+        element
+            .styles
+            .position
+            .set(Position::default().with_value(PositionValues::Right));
+    }
+    gui.update();
+    assert_eq!(messages(&mut gui), vec!["enter"]);
+    gui.update();
+    assert!(messages(&mut gui).is_empty());
+}
+
+#[test]
+fn clipped_part_of_an_element_is_not_entered() {
+    let mut gui = Gui::new_headless((200, 100));
+    let square = square(&mut gui, PositionValues::Right);
+    // spans 50..150, the square sticks out of it on the side it is placed at
+    let mut clip = Element::new().with_children(Children::Element(square));
+    clip.styles.width.set(px(100.0));
+    clip.styles.overflow.set(Overflow::Hidden);
+    let clip = gui.add_element(clip);
+    let root = gui.add_element(Element::new().with_children(Children::Element(clip)));
+    gui.set_entry(Some(root));
+    gui.update();
+    move_mouse(&mut gui, 40.0, 50.0);
+    gui.update();
+    messages(&mut gui);
+
+    let place = |gui: &mut Gui<&'static str>, position| {
+        if let Some(element) = gui.get_element_mut(square) {
+            element
+                .styles
+                .position
+                .set(Position::default().with_value(position));
+        }
+        gui.update();
+        messages(gui)
+    };
+    // covers the mouse, but only where it is clipped away
+    assert!(place(&mut gui, PositionValues::Left).is_empty());
+    assert!(place(&mut gui, PositionValues::Right).is_empty());
+    move_mouse(&mut gui, 60.0, 50.0);
+    gui.update();
+    messages(&mut gui);
+    assert_eq!(place(&mut gui, PositionValues::Left), vec!["enter"]);
+}
+
+#[test]
+fn moving_clip_uncovers_the_mouse() {
+    let mut gui = Gui::new_headless((200, 100));
+    // stays in the middle of the window wherever the clip goes
+    let square = square(&mut gui, PositionValues::Center);
+    if let Some(element) = gui.get_element_mut(square) {
+        let position = element.styles.position.get().clone();
+        element
+            .styles
+            .position
+            .set(position.with_parent(Parent::ViewPort));
+    }
+    let mut clip = Element::new().with_children(Children::Element(square));
+    clip.styles.width.set(px(100.0));
+    clip.styles.overflow.set(Overflow::Hidden);
+    let clip = gui.add_element(clip);
+    let root = gui.add_element(Element::new().with_children(Children::Element(clip)));
+    gui.set_entry(Some(root));
+    gui.update();
+    move_mouse(&mut gui, 100.0, 50.0);
+    gui.update();
+    messages(&mut gui);
+
+    if let Some(element) = gui.get_element_mut(clip) {
+        element
+            .styles
+            .position
+            .set(Position::default().with_value(PositionValues::Left));
+    }
+    gui.update();
+    assert_eq!(messages(&mut gui), vec!["leave"]);
+}
+
+#[test]
+fn removed_elements_are_reported_without_input() {
+    let mut gui = Gui::new_headless((200, 100));
+    let mut child = Element::new();
+    child.events.listen(EventTypes::Removed, "removed");
+    let child = gui.add_element(child);
+    let root = gui.add_element(Element::new().with_children(Children::Element(child)));
+    gui.set_entry(Some(root));
+    gui.update();
+
+    gui.remove_subtree(root);
+    let event = gui.poll_event().unwrap();
+    assert_eq!((event.msg, event.key), ("removed", child));
+    assert!(event.window_event.is_none());
+    assert!(gui.poll_event().is_none());
+}
@@ -0,0 +1,185 @@
+use rugui::{
+    layout::{Flex, FlexDirection, FlexItem, Grid, GridItem, Track},
+    styles::{Edges, ScaleMode, ScreenMatch, Value, Values},
+    Children, Element, Gui, Section,
+};
+
+mod common;
+
+use common::{px, rect, sized};
+
+#[test]
+fn headless_layout_fills_the_window() {
+    let mut gui: Gui<()> = Gui::new_headless((800, 600));
+    let root = gui.add_element(Element::new());
+    gui.set_entry(Some(root));
+    gui.update();
+
+    assert_eq!(rect(&gui, root), (0.0, 0.0, 800.0, 600.0));
+}
+
+#[test]
+fn rows_split_the_height() {
+    let mut gui: Gui<()> = Gui::new_headless((400, 300));
+    let header = gui.add_element(Element::new());
+    let body = gui.add_element(Element::new());
+    let mut root = Element::new().with_children(Children::Rows {
+        children: vec![
+            Section {
+                element: header,
+                size: Some(px(100.0)),
+            },
+            Section {
+                element: body,
+                size: None,
+            },
+        ],
+        spacing: None,
+        padding: None,
+        align: Default::default(),
+    });
+    root.styles.padding.set(Edges::all(px(10.0)));
+    let root = gui.add_element(root);
+    gui.set_entry(Some(root));
+    gui.update();
+
+    assert_eq!(rect(&gui, header), (10.0, 10.0, 380.0, 100.0));
+    assert_eq!(rect(&gui, body), (10.0, 110.0, 380.0, 180.0));
+}
+
+#[test]
+fn children_follow_a_resize() {
+    let mut gui: Gui<()> = Gui::new_headless((400, 300));
+    let child = sized(&mut gui, 100.0, 50.0);
+    let root = gui.add_element(Element::new().with_children(Children::Element(child)));
+    gui.set_entry(Some(root));
+    gui.update();
+    assert_eq!(rect(&gui, child), (150.0, 125.0, 100.0, 50.0));
+
+    gui.set_size((600, 400));
+    gui.update();
+    assert_eq!(rect(&gui, child), (250.0, 175.0, 100.0, 50.0));
+}
+
+#[test]
+fn flex_shares_leftover_space() {
+    let mut gui: Gui<()> = Gui::new_headless((500, 100));
+    let fixed = sized(&mut gui, 100.0, 50.0);
+    let grows = sized(&mut gui, 100.0, 50.0);
+    let root = gui.add_element(Element::new().with_children(Children::Flex(Flex::new(
+        FlexDirection::Row,
+        vec![FlexItem::new(fixed), FlexItem::new(grows).with_grow(1.0)],
+    ))));
+    gui.set_entry(Some(root));
+    gui.update();
+
+    assert_eq!(rect(&gui, fixed), (0.0, 0.0, 100.0, 100.0));
+    assert_eq!(rect(&gui, grows), (100.0, 0.0, 400.0, 100.0));
+}
+
+#[test]
+fn flex_min_wins_over_max() {
+    let mut gui: Gui<()> = Gui::new_headless((500, 100));
+    let item = sized(&mut gui, 100.0, 50.0);
+    if let Some(element) = gui.get_element_mut(item) {
+        element.styles.min_width.set(Some(px(200.0)));
+        element.styles.max_width.set(Some(px(100.0)));
+    }
+    let root = gui.add_element(Element::new().with_children(Children::Flex(Flex::new(
+        FlexDirection::Row,
+        vec![FlexItem::new(item).with_grow(1.0)],
+    ))));
+    gui.set_entry(Some(root));
+    gui.update();
+
+    assert_eq!(rect(&gui, item).2, 200.0);
+}
+
+#[test]
+fn grid_auto_tracks_fit_their_content() {
+    let mut gui: Gui<()> = Gui::new_headless((400, 100));
+    let icon = sized(&mut gui, 48.0, 48.0);
+    let filler = gui.add_element(Element::new());
+    let mut limited = Element::new();
+    limited.styles.min_width.set(Some(px(30.0)));
+    let limited = gui.add_element(limited);
+    let rest = gui.add_element(Element::new());
+    let grid = Grid::new(
+        vec![Track::Auto, Track::Auto, Track::Auto, Track::Fraction(1.0)],
+        vec![Track::Fraction(1.0)],
+        vec![
+            GridItem::new(icon, 0, 0),
+            GridItem::new(filler, 1, 0),
+            GridItem::new(limited, 2, 0),
+            GridItem::new(rest, 3, 0),
+        ],
+    );
+    let root = gui.add_element(Element::new().with_children(Children::Grid(grid)));
+    gui.set_entry(Some(root));
+    gui.update();
+
+    assert_eq!(rect(&gui, filler).2, 0.0);
+    assert_eq!(rect(&gui, limited), (48.0, 0.0, 30.0, 100.0));
+    assert_eq!(rect(&gui, rest), (78.0, 0.0, 322.0, 100.0));
+}
+
+#[test]
+fn scale_mode_replaces_the_scale_factor_for_logical_sizes() {
+    let mut gui: Gui<()> = Gui::new_headless((400, 200));
+    gui.set_scale_factor(2.0);
+    let logical = gui.add_element({
+        let mut element = Element::new();
+        element
+            .styles
+            .width
+            .set(Values::Value(Value::Logical(50.0)));
+        element
+    });
+    let pixel = sized(&mut gui, 50.0, 50.0);
+    let root = gui.add_element(Element::new().with_children(Children::Rows {
+        children: vec![
+            Section {
+                element: logical,
+                size: None,
+            },
+            Section {
+                element: pixel,
+                size: None,
+            },
+        ],
+        spacing: None,
+        padding: None,
+        align: Default::default(),
+    }));
+    gui.set_entry(Some(root));
+    gui.update();
+    assert_eq!(rect(&gui, logical).2, 100.0);
+
+    gui.set_scale_mode(ScaleMode::ScaleWithScreen {
+        reference: (200.0, 100.0),
+        fit: ScreenMatch::Expand,
+    });
+    gui.update();
+    assert_eq!(gui.ui_scale(), 2.0);
+    assert_eq!(rect(&gui, logical).2, 100.0);
+    assert_eq!(rect(&gui, pixel).2, 50.0);
+
+    gui.set_size((800, 400));
+    gui.update();
+    assert_eq!(rect(&gui, logical).2, 200.0);
+    assert_eq!(rect(&gui, pixel).2, 50.0);
+}
+
+#[test]
+fn stale_children_are_reported_once_per_update() {
+    let mut gui: Gui<()> = Gui::new_headless((400, 300));
+    let child = gui.add_element(Element::new());
+    gui.remove_element(child);
+    let root = gui.add_element(Element::new().with_children(Children::Element(child)));
+    gui.set_entry(Some(root));
+    gui.update();
+    gui.update();
+
+    assert_eq!(gui.stale_keys(), &[child]);
+    assert_eq!(rect(&gui, root), (0.0, 0.0, 400.0, 300.0));
+}
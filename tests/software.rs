@@ -0,0 +1,90 @@
+use std::sync::Arc;
+
+use image::{DynamicImage, Rgba, RgbaImage};
+use rugui::{
+    software::SoftwareRenderer,
+    styles::{ColorPoint, Colors, Edges, LinearGradient, Position, PositionValues},
+    texture::Texture,
+    Children, Element, ElementKey, Gui, Section,
+};
+
+mod common;
+
+use common::px;
+
+const GOLDEN: &str = "tests/golden/software.png";
+
+fn column(element: ElementKey) -> Section {
+    Section {
+        element,
+        size: None,
+    }
+}
+
+fn scene() -> Gui<()> {
+    let mut gui: Gui<()> = Gui::new_headless((128, 48));
+
+    let mut rounded = Element::new();
+    rounded.styles.bg_color.set(Colors::RED);
+    rounded.styles.edges_radius.set(px(8.0));
+    let rounded = gui.add_element(rounded);
+
+    let mut gradient = Element::new();
+    gradient
+        .styles
+        .bg_linear_gradient
+        .set(Some(LinearGradient::new(
+            ColorPoint {
+                position: Position::default().with_value(PositionValues::Left),
+                color: Colors::BLUE,
+            },
+            ColorPoint {
+                position: Position::default().with_value(PositionValues::Right),
+                color: Colors::GREEN,
+            },
+        )));
+    let gradient = gui.add_element(gradient);
+
+    let checker = RgbaImage::from_fn(4, 4, |x, y| match (x + y) % 2 {
+        0 => Rgba([255, 255, 255, 255]),
+        _ => Rgba([0, 0, 0, 255]),
+    });
+    let texture = Texture::new(&DynamicImage::ImageRgba8(checker), Some("checker"));
+    let mut textured = Element::new();
+    textured.styles.bg_texture.set(Some(Arc::new(texture)));
+    let textured = gui.add_element(textured);
+
+    let mut root = Element::new().with_children(Children::Columns {
+        children: vec![column(rounded), column(gradient), column(textured)],
+        spacing: Some(px(4.0)),
+        padding: None,
+        align: Default::default(),
+    });
+    root.styles.padding.set(Edges::all(px(4.0)));
+    let root = gui.add_element(root);
+    gui.set_entry(Some(root));
+    gui.update();
+    gui
+}
+
+#[test]
+fn software_render_matches_golden_image() {
+    let image = SoftwareRenderer::new().render(&mut scene());
+
+    // run with RUGUI_BLESS=1 to accept a new rendering
+    if std::env::var_os("RUGUI_BLESS").is_some() {
+        std::fs::create_dir_all("tests/golden").unwrap();
+        image.save(GOLDEN).unwrap();
+        return;
+    }
+    let golden = image::open(GOLDEN)
+        .unwrap_or_else(|error| panic!("can't open {}: {}", GOLDEN, error))
+        .to_rgba8();
+    assert_eq!(image.dimensions(), golden.dimensions());
+    let differing = image
+        .pixels()
+        .zip(golden.pixels())
+        .filter(|(a, b)| a.0.iter().zip(b.0).any(|(a, b)| a.abs_diff(b) > 1))
+        .count();
+    assert_eq!(differing, 0, "{} pixels differ from {}", differing, GOLDEN);
+}
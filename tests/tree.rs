@@ -0,0 +1,47 @@
+use rugui::{Children, Element, Gui};
+
+#[test]
+fn parents_are_linked_without_a_layout() {
+    let mut gui: Gui<()> = Gui::new_headless((400, 300));
+    let child = gui.add_element(Element::new());
+    let mut hidden = Element::new().with_children(Children::Element(child));
+    hidden.styles.visible = false;
+    let hidden = gui.add_element(hidden);
+    let root = gui.add_element(Element::new().with_children(Children::Element(hidden)));
+    gui.set_entry(Some(root));
+    gui.update();
+
+    assert_eq!(gui.ancestors(child).collect::<Vec<_>>(), vec![hidden, root]);
+
+    let other = gui.add_element(Element::new());
+    gui.set_children(root, Children::Layers(vec![hidden, other]));
+    assert_eq!(gui.parent_of(other), Some(root));
+}
+
+#[test]
+fn moved_child_leaves_its_old_parent() {
+    let mut gui: Gui<()> = Gui::new_headless((400, 300));
+    let child = gui.add_element(Element::new());
+    let old = gui.add_element(Element::new().with_children(Children::Element(child)));
+    let new = gui.add_element(Element::new());
+
+    gui.set_children(new, Children::Element(child));
+
+    assert_eq!(gui.parent_of(child), Some(new));
+    assert_eq!(gui.children_of(old).count(), 0);
+    assert_eq!(gui.children_of(new).collect::<Vec<_>>(), vec![child]);
+}
+
+#[test]
+fn labels_follow_set_label() {
+    let mut gui: Gui<()> = Gui::new_headless((400, 300));
+    let first = gui.add_element(Element::new().with_label("item"));
+    let second = gui.add_element(Element::new());
+    gui.set_label(second, Some("item"));
+    assert_eq!(gui.find_all_by_label("item"), vec![first, second]);
+
+    gui.set_label(first, Some("other"));
+    assert_eq!(gui.find_by_label("item"), Some(second));
+    assert_eq!(gui.get_element(first).unwrap().label(), Some("other"));
+    assert_eq!(gui.find_by_label("other"), Some(first));
+}
@@ -0,0 +1,169 @@
+use rugui::{
+    styles::{Container, Side, Values, ValuesError, ViewPort},
+    Point,
+};
+
+fn container() -> Container {
+    Container {
+        position: Point::new(0.0, 0.0),
+        size: Point::new(200.0, 100.0),
+        rotation: 0.0,
+        image: None,
+        text: None,
+        content: None,
+        elements: Vec::new(),
+    }
+}
+
+/// Parses `input` and computes it in a 200x100 container and a 400x300
+/// view port with two physical pixels per logical pixel
+fn calc(input: &str) -> f32 {
+    let values = Values::parse(input).unwrap_or_else(|error| panic!("{}: {}", input, error));
+    values.calc(&container(), &ViewPort(400.0, 300.0, 2.0))
+}
+
+fn error(input: &str) -> ValuesError {
+    Values::parse(input).expect_err(input)
+}
+
+fn unexpected(position: usize, found: char, expected: &'static str) -> ValuesError {
+    ValuesError::Unexpected {
+        position,
+        found,
+        expected,
+    }
+}
+
+#[test]
+fn operators_follow_precedence() {
+    assert_eq!(calc("2 + 3 * 4"), 14.0);
+    assert_eq!(calc("(2 + 3) * 4"), 20.0);
+    assert_eq!(calc("10 - 4 - 3"), 3.0);
+    assert_eq!(calc("8 / 2 / 2"), 2.0);
+    assert_eq!(calc("2*3+4*5"), 26.0);
+}
+
+#[test]
+fn unary_minus_negates_a_factor() {
+    assert_eq!(calc("-5"), -5.0);
+    assert_eq!(calc("-(2 + 3) * 2"), -10.0);
+    assert_eq!(calc("4 - -2"), 6.0);
+    assert_eq!(calc("-2 * -3"), 6.0);
+}
+
+#[test]
+fn numbers_and_units() {
+    assert_eq!(calc("5"), 5.0);
+    assert_eq!(calc("5px"), 5.0);
+    assert_eq!(calc(".5"), 0.5);
+    assert_eq!(calc("1.5e2"), 150.0);
+    assert_eq!(calc("1E+2"), 100.0);
+    assert_eq!(calc("1000e-3"), 1.0);
+    assert_eq!(calc("10lpx"), 20.0);
+    assert_eq!(calc("50%"), 100.0);
+    assert_eq!(calc("10cqw"), 20.0);
+    assert_eq!(calc("10cqh"), 10.0);
+    assert_eq!(calc("10cqmin"), 10.0);
+    assert_eq!(calc("10cqmax"), 20.0);
+    assert_eq!(calc("10vw"), 40.0);
+    assert_eq!(calc("10vh"), 30.0);
+    assert_eq!(calc("10vmin"), 30.0);
+    assert_eq!(calc("10vmax"), 40.0);
+
+    let height = Values::parse_for("50%", Side::Height).unwrap();
+    assert_eq!(
+        height.calc(&container(), &ViewPort(400.0, 300.0, 2.0)),
+        50.0
+    );
+}
+
+#[test]
+fn functions() {
+    assert_eq!(calc("min(30px, 10, 20)"), 10.0);
+    assert_eq!(calc("max(30px, 10, 20)"), 30.0);
+    assert_eq!(calc("min(50%)"), 100.0);
+    assert_eq!(calc("clamp(10, 50, 20)"), 20.0);
+    assert_eq!(calc("clamp(10, 5, 20)"), 10.0);
+    assert_eq!(calc("pow(2, 3)"), 8.0);
+    assert_eq!(calc("mod(7, 3)"), 1.0);
+    assert_eq!(calc("round(1.6)"), 2.0);
+    assert_eq!(calc("floor(1.6)"), 1.0);
+    assert_eq!(calc("ceil(1.2)"), 2.0);
+    assert_eq!(calc("sqrt(16)"), 4.0);
+    assert_eq!(calc("abs(-3)"), 3.0);
+    assert_eq!(calc("max(10%, 5vw) - min(1, 2) * 2"), 18.0);
+}
+
+#[test]
+fn empty() {
+    assert_eq!(error(""), ValuesError::Empty);
+    assert_eq!(error("   "), ValuesError::Empty);
+}
+
+#[test]
+fn unexpected_character() {
+    assert_eq!(error("1 + *"), unexpected(4, '*', "a value"));
+    assert_eq!(error("1 2"), unexpected(2, '2', "an operator"));
+    assert_eq!(error("1.2.3"), unexpected(3, '.', "an operator"));
+    assert_eq!(error("1.x"), unexpected(2, 'x', "a digit after '.'"));
+    assert_eq!(
+        error("min 1"),
+        unexpected(4, '1', "'(' after the function name")
+    );
+    assert_eq!(error("min(1; 2)"), unexpected(5, ';', "',' or ')'"));
+    assert_eq!(error("(1))"), unexpected(3, ')', "an operator"));
+}
+
+#[test]
+fn unexpected_end() {
+    let end = |expected| ValuesError::UnexpectedEnd { expected };
+    assert_eq!(error("1 +"), end("a value"));
+    assert_eq!(error("-"), end("a value"));
+    assert_eq!(error("1."), end("a digit after '.'"));
+    assert_eq!(error("min"), end("'(' after the function name"));
+}
+
+#[test]
+fn unknown_unit() {
+    let unit = |position, unit: &str| ValuesError::UnknownUnit {
+        position,
+        unit: unit.to_string(),
+    };
+    assert_eq!(error("10em"), unit(2, "em"));
+    assert_eq!(error("1 + 1e"), unit(5, "e"));
+    assert_eq!(error("1e+"), unit(1, "e"));
+    assert_eq!(error("1e3e4"), unit(3, "e"));
+}
+
+#[test]
+fn unknown_function() {
+    assert_eq!(
+        error("1 + foo(1)"),
+        ValuesError::UnknownFunction {
+            position: 4,
+            name: "foo".to_string(),
+        }
+    );
+}
+
+#[test]
+fn argument_count() {
+    let count = |name: &str, expected, found| ValuesError::ArgumentCount {
+        position: 0,
+        name: name.to_string(),
+        expected,
+        found,
+    };
+    assert_eq!(error("clamp(1, 2)"), count("clamp", "3 arguments", 2));
+    assert_eq!(error("min()"), count("min", "at least one argument", 0));
+    assert_eq!(error("pow(2)"), count("pow", "2 arguments", 1));
+    assert_eq!(error("abs(1, 2)"), count("abs", "1 argument", 2));
+}
+
+#[test]
+fn unclosed_parenthesis() {
+    let unclosed = |position| ValuesError::UnclosedParenthesis { position };
+    assert_eq!(error("(1 + 2"), unclosed(0));
+    assert_eq!(error("2 * (1 + (2)"), unclosed(4));
+    assert_eq!(error("max(1, 2"), unclosed(0));
+}